@@ -46,7 +46,10 @@ fn write_partial_document(session: &mut ServiceSession, doc_id: &str) -> errors:
         "tests",
         Some(doc_id),
         &obj,
-        documents::WriteOptions { merge: true },
+        documents::WriteOptions {
+            merge: true,
+            ..Default::default()
+        },
     )
 }
 
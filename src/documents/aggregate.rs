@@ -0,0 +1,268 @@
+use super::*;
+use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
+
+impl dto::Aggregation {
+    /// Counts the matching documents. The result is an integer.
+    ///
+    /// ## Arguments
+    /// * 'alias' The name of the result field
+    pub fn count(alias: &str) -> Self {
+        dto::Aggregation {
+            alias: Some(alias.to_owned()),
+            count: Some(dto::Count::default()),
+            ..Default::default()
+        }
+    }
+
+    /// Sums up the values of the given field over all matching documents.
+    /// The result is an integer if all values are integers and a double otherwise.
+    /// Non-numeric values are ignored.
+    ///
+    /// ## Arguments
+    /// * 'alias' The name of the result field
+    /// * 'field' The field to sum up. For example "price".
+    pub fn sum(alias: &str, field: &str) -> Self {
+        dto::Aggregation {
+            alias: Some(alias.to_owned()),
+            sum: Some(dto::Sum {
+                field: dto::FieldReference {
+                    field_path: field.to_owned(),
+                },
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Averages the values of the given field over all matching documents.
+    /// The result is a double, or `null` if there are no numeric values.
+    ///
+    /// ## Arguments
+    /// * 'alias' The name of the result field
+    /// * 'field' The field to average. For example "price".
+    pub fn avg(alias: &str, field: &str) -> Self {
+        dto::Aggregation {
+            alias: Some(alias.to_owned()),
+            avg: Some(dto::Avg {
+                field: dto::FieldReference {
+                    field_path: field.to_owned(),
+                },
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CountResult {
+    count: i64,
+}
+
+///
+/// Counts the documents that match the given query, without transferring the documents.
+///
+/// Example:
+/// ```no_run
+/// use firestore_db_and_auth::{documents, dto};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let query = documents::QueryBuilder::new("tests").field_filter("an_int", dto::FieldOperator::GREATER_THAN, 10);
+/// let count = documents::count(&session, &query)?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'query' The query. Use `QueryBuilder::new("my_collection")` to count all documents of a collection.
+pub fn count(auth: &impl FirebaseAuthBearer, query: &QueryBuilder) -> Result<i64> {
+    let result: CountResult = aggregate(auth, query, &[dto::Aggregation::count("count")])?;
+    Ok(result.count)
+}
+
+///
+/// Counts the documents that match the given query, without transferring the documents.
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'query' The query. Use `QueryBuilder::new("my_collection")` to count all documents of a collection.
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn count_async(auth: &mut impl FirebaseAuthBearerAsync, query: &QueryBuilder) -> Result<i64> {
+    let result: CountResult = aggregate_async(auth, query, &[dto::Aggregation::count("count")]).await?;
+    Ok(result.count)
+}
+
+///
+/// Runs the given aggregations (count, sum, average) over the documents that match the given query.
+///
+/// The result fields are named after the aliases of the aggregations and deserialized into `T`.
+///
+/// Example:
+/// ```no_run
+/// # use serde::Deserialize;
+/// use firestore_db_and_auth::{documents, dto};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// #[derive(Deserialize)]
+/// struct Stats { count: i64, total: f64, average: Option<f64> }
+///
+/// let query = documents::QueryBuilder::new("orders");
+/// let stats: Stats = documents::aggregate(&session, &query, &[
+///     dto::Aggregation::count("count"),
+///     dto::Aggregation::sum("total", "price"),
+///     dto::Aggregation::avg("average", "price"),
+/// ])?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'query' The query
+/// * 'aggregations' The aggregations. Each needs a unique alias.
+pub fn aggregate<T>(
+    auth: &impl FirebaseAuthBearer,
+    query: &QueryBuilder,
+    aggregations: &[dto::Aggregation],
+) -> Result<T>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let url = firebase_url_method(
+        auth.project_id(),
+        auth.database_id(),
+        query.parent_path(),
+        "runAggregationQuery",
+    );
+    let request = aggregation_request(query, aggregations)?;
+
+    let resp = auth
+        .client()
+        .post(url)
+        .bearer_auth(auth.access_token().to_owned())
+        .json(&request)
+        .send()?;
+
+    let resp = extract_google_api_error(resp, || query.context())?;
+
+    let json: Vec<dto::RunAggregationQueryResponse> = resp.json()?;
+    aggregation_result(query, json)
+}
+
+///
+/// Runs the given aggregations (count, sum, average) over the documents that match the given query.
+///
+/// The result fields are named after the aliases of the aggregations and deserialized into `T`.
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'query' The query
+/// * 'aggregations' The aggregations. Each needs a unique alias.
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn aggregate_async<T>(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    query: &QueryBuilder,
+    aggregations: &[dto::Aggregation],
+) -> Result<T>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let url = firebase_url_method(
+        auth.project_id(),
+        auth.database_id(),
+        query.parent_path(),
+        "runAggregationQuery",
+    );
+    let request = aggregation_request(query, aggregations)?;
+
+    let resp = auth
+        .client_async()
+        .post(&url)
+        .bearer_auth(auth.access_token().await.to_string())
+        .json(&request)
+        .send()
+        .await?;
+
+    let resp = extract_google_api_error_async(resp, || query.context()).await?;
+
+    let json: Vec<dto::RunAggregationQueryResponse> = resp.json().await?;
+    aggregation_result(query, json)
+}
+
+fn aggregation_request(
+    query: &QueryBuilder,
+    aggregations: &[dto::Aggregation],
+) -> Result<dto::RunAggregationQueryRequest> {
+    Ok(dto::RunAggregationQueryRequest {
+        structured_aggregation_query: Some(dto::StructuredAggregationQuery {
+            structured_query: Some(query.build_validated()?),
+            aggregations: Some(aggregations.to_vec()),
+        }),
+        ..Default::default()
+    })
+}
+
+/// The response is a list with one entry that contains the result.
+fn aggregation_result<T>(query: &QueryBuilder, responses: Vec<dto::RunAggregationQueryResponse>) -> Result<T>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let result = responses
+        .into_iter()
+        .find_map(|r| r.result)
+        .ok_or(FirebaseError::Generic("No result in aggregation query response"))?;
+
+    document_to_pod(&dto::Document {
+        fields: result.aggregate_fields,
+        name: query.context(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_deserializes_aggregation_results() -> Result<()> {
+        #[derive(Deserialize)]
+        struct Stats {
+            count: i64,
+            total: f64,
+            average: Option<f64>,
+        }
+
+        let json: Vec<dto::RunAggregationQueryResponse> = serde_json::from_str(
+            r#"[{
+            "result": {"aggregateFields": {
+                "count": {"integerValue": "3"},
+                "total": {"integerValue": "12"},
+                "average": {"nullValue": null}
+            }},
+            "readTime": "2020-04-28T14:52:51.250511Z"
+            }]"#,
+        )?;
+        let stats: Stats = aggregation_result(&QueryBuilder::new("orders"), json)?;
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.total, 12.0);
+        assert!(stats.average.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn it_serializes_aggregations() -> Result<()> {
+        let request = aggregation_request(
+            &QueryBuilder::new("orders"),
+            &[
+                dto::Aggregation::count("count"),
+                dto::Aggregation::sum("total", "price"),
+            ],
+        )?;
+        let json = serde_json::to_value(&request)?;
+        let aggregations = &json["structuredAggregationQuery"]["aggregations"];
+        assert_eq!(aggregations[0], serde_json::json!({"alias": "count", "count": {}}));
+        assert_eq!(aggregations[1]["sum"]["field"]["fieldPath"], "price");
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use super::*;
+use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
+use std::collections::{HashMap, HashSet};
+
+/// An entry of the [`batch_get()`] result. There is one entry per requested document path.
+pub enum BatchGetEntry<T> {
+    /// The document exists. A tuple of the deserialized document and its metadata
+    /// (with .name, .create_time, .update_time fields) is returned.
+    Found(T, dto::Document),
+    /// The document does not exist. Contains the absolute document name.
+    Missing(String),
+}
+
+impl<T> BatchGetEntry<T> {
+    /// Returns the document, if it exists
+    pub fn found(self) -> Option<T> {
+        match self {
+            BatchGetEntry::Found(document, _) => Some(document),
+            BatchGetEntry::Missing(_) => None,
+        }
+    }
+}
+
+///
+/// Reads multiple documents of a specific type in one request.
+///
+/// The returned entries are in the same order as the given paths.
+/// Documents that do not exist are returned as [`BatchGetEntry::Missing`].
+///
+/// Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct DemoDTO { a_string: String, an_int: u32, }
+///
+/// use firestore_db_and_auth::documents::{self, BatchGetEntry};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let entries = documents::batch_get::<DemoDTO>(&session, &["tests/a", "tests/b"])?;
+/// for entry in entries {
+///     match entry {
+///         BatchGetEntry::Found(doc, metadata) => println!("{}: {:?}", metadata.name, doc),
+///         BatchGetEntry::Missing(name) => println!("{} does not exist", name),
+///     }
+/// }
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'paths' The relative collection paths and document ids, for example "my_collection/document_id"
+pub fn batch_get<T>(auth: &impl FirebaseAuthBearer, paths: &[impl AsRef<str>]) -> Result<Vec<BatchGetEntry<T>>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    batch_get_in_transaction(auth, paths, None)
+}
+
+/// Reads the documents as part of the given transaction, if any
+pub(crate) fn batch_get_in_transaction<T>(
+    auth: &impl FirebaseAuthBearer,
+    paths: &[impl AsRef<str>],
+    transaction: Option<&str>,
+) -> Result<Vec<BatchGetEntry<T>>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "batchGet");
+    let names = document_names(auth.project_id(), auth.database_id(), paths);
+    let request = dto::BatchGetDocumentsRequest {
+        documents: Some(names.clone()),
+        transaction: transaction.map(str::to_owned),
+        ..Default::default()
+    };
+
+    let resp = auth
+        .client()
+        .post(url)
+        .bearer_auth(auth.access_token().to_owned())
+        .json(&request)
+        .send()?;
+
+    let resp = extract_google_api_error(resp, || names.join(","))?;
+
+    let json: Vec<dto::BatchGetDocumentsResponse> = resp.json()?;
+    batch_get_entries(names, json)
+}
+
+///
+/// Reads multiple documents of a specific type in one request.
+///
+/// The returned entries are in the same order as the given paths.
+/// Documents that do not exist are returned as [`BatchGetEntry::Missing`].
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'paths' The relative collection paths and document ids, for example "my_collection/document_id"
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn batch_get_async<T>(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    paths: &[impl AsRef<str>],
+) -> Result<Vec<BatchGetEntry<T>>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    batch_get_in_transaction_async(auth, paths, None).await
+}
+
+/// Reads the documents as part of the given transaction, if any
+pub(crate) async fn batch_get_in_transaction_async<T>(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    paths: &[impl AsRef<str>],
+    transaction: Option<&str>,
+) -> Result<Vec<BatchGetEntry<T>>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "batchGet");
+    let names = document_names(auth.project_id(), auth.database_id(), paths);
+    let request = dto::BatchGetDocumentsRequest {
+        documents: Some(names.clone()),
+        transaction: transaction.map(str::to_owned),
+        ..Default::default()
+    };
+
+    let resp = auth
+        .client_async()
+        .post(&url)
+        .bearer_auth(auth.access_token().await.to_string())
+        .json(&request)
+        .send()
+        .await?;
+
+    let resp = extract_google_api_error_async(resp, || names.join(",")).await?;
+
+    let json: Vec<dto::BatchGetDocumentsResponse> = resp.json().await?;
+    batch_get_entries(names, json)
+}
+
+fn document_names(project_id: &str, database_id: &str, paths: &[impl AsRef<str>]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| firebase_document_name(project_id, database_id, path.as_ref()))
+        .collect()
+}
+
+/// The responses arrive in any order. Sort them into the order of the requested document names.
+/// Every requested document must be reported as either found or missing.
+pub(crate) fn batch_get_entries<T>(
+    names: Vec<String>,
+    responses: Vec<dto::BatchGetDocumentsResponse>,
+) -> Result<Vec<BatchGetEntry<T>>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let mut documents: HashMap<String, dto::Document> = HashMap::new();
+    let mut missing: HashSet<String> = HashSet::new();
+    for response in responses {
+        if let Some(document) = response.found {
+            documents.insert(document.name.clone(), document);
+        } else if let Some(name) = response.missing {
+            missing.insert(name);
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| match documents.get(&name) {
+            Some(document) => Ok(BatchGetEntry::Found(
+                document_to_pod(document)?,
+                dto::Document {
+                    fields: None,
+                    ..document.clone()
+                },
+            )),
+            None if missing.contains(&name) => Ok(BatchGetEntry::Missing(name)),
+            None => Err(FirebaseError::Generic(
+                "The batch get response neither contains a requested document nor reports it missing",
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_sorts_batch_get_entries_by_requested_path() -> Result<()> {
+        #[derive(Deserialize)]
+        struct Demo {
+            a: i64,
+        }
+
+        let json: Vec<dto::BatchGetDocumentsResponse> = serde_json::from_str(
+            r#"[
+            {"missing": "projects/p/databases/(default)/documents/tests/missing", "readTime": "2020-04-28T14:52:51.250511Z"},
+            {"found": {
+                "name": "projects/p/databases/(default)/documents/tests/two",
+                "fields": {"a": {"integerValue": "2"}}
+            }},
+            {"found": {
+                "name": "projects/p/databases/(default)/documents/tests/one",
+                "fields": {"a": {"integerValue": "1"}}
+            }}
+            ]"#,
+        )?;
+        let names = document_names("p", DEFAULT_DATABASE_ID, &["tests/one", "tests/missing", "tests/two"]);
+        let entries: Vec<BatchGetEntry<Demo>> = batch_get_entries(names, json)?;
+
+        assert!(matches!(&entries[0], BatchGetEntry::Found(d, m) if d.a == 1 && m.fields.is_none()));
+        assert!(matches!(&entries[1], BatchGetEntry::Missing(name) if name.ends_with("tests/missing")));
+        assert!(matches!(&entries[2], BatchGetEntry::Found(d, _) if d.a == 2));
+
+        let names = document_names("p", DEFAULT_DATABASE_ID, &["tests/unknown"]);
+        assert!(batch_get_entries::<Demo>(names, vec![]).is_err());
+        Ok(())
+    }
+}
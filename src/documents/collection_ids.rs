@@ -0,0 +1,173 @@
+use super::pager::{page_request, AsyncPager, Pager};
+use super::*;
+use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
+use futures_core::stream::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+///
+/// Lists the ids of the collections under the database root or under a document.
+///
+/// Use it as an iterator. The paging API is used internally and new pages are fetched lazily.
+///
+/// Example:
+/// ```no_run
+/// use firestore_db_and_auth::documents;
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// // All root collections
+/// for collection_id in documents::list_collection_ids(&session, None) {
+///     println!("{}", collection_id?);
+/// }
+/// // The sub-collections of a document
+/// let sub_collections: Vec<String> =
+///     documents::list_collection_ids(&session, Some("tests/test")).collect::<Result<_, _>>()?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'parent' The relative path of the parent document, for example "my_collection/document_id".
+///   `None` for the root collections.
+pub fn list_collection_ids<'a, BEARER>(auth: &'a BEARER, parent: Option<&str>) -> CollectionIds<'a, BEARER>
+where
+    BEARER: FirebaseAuthBearer,
+{
+    let url = collection_ids_url(auth.project_id(), auth.database_id(), parent);
+    let parent = parent.map(str::to_owned);
+    CollectionIds {
+        pager: Pager::new(move |page_token| get_collection_ids(auth, &url, &parent, page_token).map(Into::into)),
+        phantom: std::marker::PhantomData,
+    }
+}
+
+///
+/// Lists the ids of the collections under the database root or under a document.
+/// See [`list_collection_ids`].
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'parent' The relative path of the parent document, for example "my_collection/document_id".
+///   `None` for the root collections.
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub fn list_collection_ids_async<BEARER>(auth: BEARER, parent: Option<&str>) -> AsyncCollectionIds<BEARER>
+where
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
+{
+    let url = collection_ids_url(auth.project_id(), auth.database_id(), parent);
+    let parent = parent.map(str::to_owned);
+    AsyncCollectionIds {
+        pager: AsyncPager::new(auth, move |auth, page_token| {
+            page_request(get_collection_ids_async(auth, url.clone(), parent.clone(), page_token))
+        }),
+    }
+}
+
+/// This type is returned as a result by [`list_collection_ids()`].
+/// Use it as an iterator of collection ids. New pages are fetched lazily.
+pub struct CollectionIds<'a, BEARER> {
+    pager: Pager<'a, String>,
+    phantom: std::marker::PhantomData<&'a BEARER>,
+}
+
+/// This type is returned as a result by [`list_collection_ids_async()`].
+/// Use it as a [`Stream`] of collection ids. New pages are fetched lazily.
+pub struct AsyncCollectionIds<BEARER> {
+    pager: AsyncPager<BEARER, String>,
+}
+
+/// The url of the listCollectionIds method of the parent document or the database root
+fn collection_ids_url(project_id: &str, database_id: &str, parent: Option<&str>) -> String {
+    firebase_url_method(project_id, database_id, parent, "listCollectionIds")
+}
+
+/// The error context of a request: The parent document or the database root
+fn parent_context(parent: &Option<String>) -> String {
+    parent.clone().unwrap_or_else(|| "/".to_owned())
+}
+
+fn get_collection_ids(
+    auth: &impl FirebaseAuthBearer,
+    url: &str,
+    parent: &Option<String>,
+    page_token: Option<String>,
+) -> Result<dto::ListCollectionIdsResponse> {
+    let request = dto::ListCollectionIdsRequest {
+        page_token,
+        page_size: None,
+    };
+
+    let resp = auth
+        .client()
+        .post(url)
+        .bearer_auth(auth.access_token().to_owned())
+        .json(&request)
+        .send()?;
+
+    let resp = extract_google_api_error(resp, || parent_context(parent))?;
+
+    Ok(resp.json()?)
+}
+
+/// Fetches one page and returns the session, so that it can be used for the next page
+pub(crate) async fn get_collection_ids_async<BEARER: FirebaseAuthBearerAsync>(
+    mut auth: BEARER,
+    url: String,
+    parent: Option<String>,
+    page_token: Option<String>,
+) -> (BEARER, Result<dto::ListCollectionIdsResponse>) {
+    let result = async {
+        let request = dto::ListCollectionIdsRequest {
+            page_token,
+            page_size: None,
+        };
+
+        let resp = auth
+            .client_async()
+            .post(&url)
+            .bearer_auth(auth.access_token().await)
+            .json(&request)
+            .send()
+            .await?;
+
+        let resp = extract_google_api_error_async(resp, || parent_context(&parent)).await?;
+
+        Ok(resp.json().await?)
+    }
+    .await;
+    (auth, result)
+}
+
+impl<'a, BEARER> Iterator for CollectionIds<'a, BEARER>
+where
+    BEARER: FirebaseAuthBearer,
+{
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pager.next()
+    }
+}
+
+impl<BEARER> Stream for AsyncCollectionIds<BEARER>
+where
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
+{
+    type Item = Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().pager).poll_next(cx)
+    }
+}
+
+#[test]
+fn it_lists_collection_ids_of_parents() {
+    assert!(collection_ids_url("p", "(default)", None)
+        .ends_with("/projects/p/databases/(default)/documents:listCollectionIds"));
+    assert!(collection_ids_url("p", "(default)", Some("users/alice"))
+        .ends_with("/projects/p/databases/(default)/documents/users/alice:listCollectionIds"));
+    assert_eq!(parent_context(&Some("users/alice".to_owned())), "users/alice");
+    assert_eq!(parent_context(&None), "/");
+}
@@ -1,5 +1,8 @@
 use super::*;
-use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
+use crate::errors::{
+    extract_google_api_error_async, extract_google_api_error_precondition, extract_google_api_error_precondition_async,
+};
+use crate::FirebaseAuthBearerAsync;
 
 ///
 /// Deletes the document at the given path.
@@ -12,24 +15,17 @@ use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
 /// * 'path' The relative collection path and document id, for example "my_collection/document_id"
 /// * 'fail_if_not_existing' If true this method will return an error if the document does not exist.
 pub fn delete(auth: &impl FirebaseAuthBearer, path: &str, fail_if_not_existing: bool) -> Result<()> {
-    let url = firebase_url(auth.project_id(), path);
-
-    let query_request = dto::Write {
-        current_document: Some(dto::Precondition {
-            exists: match fail_if_not_existing {
-                true => Some(true),
-                false => None,
-            },
-            ..Default::default()
-        }),
-        ..Default::default()
+    let url = firebase_url(auth.project_id(), auth.database_id(), path);
+    let precondition = match fail_if_not_existing {
+        true => vec![Precondition::Exists.query_param()],
+        false => vec![],
     };
 
     let resp = auth
         .client()
         .delete(url)
+        .query(&precondition)
         .bearer_auth(auth.access_token().to_owned())
-        .json(&query_request)
         .send()?;
 
     extract_google_api_error(resp, || path.to_owned())?;
@@ -52,24 +48,17 @@ pub async fn delete_async(
     path: &str,
     fail_if_not_existing: bool,
 ) -> Result<()> {
-    let url = firebase_url(auth.project_id(), path);
-
-    let query_request = dto::Write {
-        current_document: Some(dto::Precondition {
-            exists: match fail_if_not_existing {
-                true => Some(true),
-                false => None,
-            },
-            ..Default::default()
-        }),
-        ..Default::default()
+    let url = firebase_url(auth.project_id(), auth.database_id(), path);
+    let precondition = match fail_if_not_existing {
+        true => vec![Precondition::Exists.query_param()],
+        false => vec![],
     };
 
     let resp = auth
         .client_async()
         .delete(&url)
+        .query(&precondition)
         .bearer_auth(auth.access_token().await.to_string())
-        .json(&query_request)
         .send()
         .await?;
 
@@ -77,3 +66,68 @@ pub async fn delete_async(
 
     Ok(())
 }
+
+///
+/// Deletes the document at the given path, if the given precondition is met.
+/// Returns [`FirebaseError::PreconditionFailed`] otherwise.
+///
+/// Example:
+/// ```no_run
+/// use firestore_db_and_auth::documents::{self, Precondition};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+/// # let update_time = chrono::Utc::now();
+///
+/// // Only delete the document if nobody modified it since we have read it
+/// documents::delete_with_precondition(&session, "tests/service_test", Precondition::UpdateTime(update_time))?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'path' The relative collection path and document id, for example "my_collection/document_id"
+/// * 'precondition' The condition on the document
+pub fn delete_with_precondition(auth: &impl FirebaseAuthBearer, path: &str, precondition: Precondition) -> Result<()> {
+    let url = firebase_url(auth.project_id(), auth.database_id(), path);
+
+    let resp = auth
+        .client()
+        .delete(url)
+        .query(&[precondition.query_param()])
+        .bearer_auth(auth.access_token().to_owned())
+        .send()?;
+
+    extract_google_api_error_precondition(resp, || path.to_owned())?;
+
+    Ok(())
+}
+
+///
+/// Deletes the document at the given path, if the given precondition is met.
+/// Returns [`FirebaseError::PreconditionFailed`] otherwise.
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'path' The relative collection path and document id, for example "my_collection/document_id"
+/// * 'precondition' The condition on the document
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn delete_with_precondition_async(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    path: &str,
+    precondition: Precondition,
+) -> Result<()> {
+    let url = firebase_url(auth.project_id(), auth.database_id(), path);
+
+    let resp = auth
+        .client_async()
+        .delete(&url)
+        .query(&[precondition.query_param()])
+        .bearer_auth(auth.access_token().await.to_string())
+        .send()
+        .await?;
+
+    extract_google_api_error_precondition_async(resp, || path.to_owned()).await?;
+
+    Ok(())
+}
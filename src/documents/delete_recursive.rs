@@ -0,0 +1,450 @@
+use super::*;
+use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
+use futures_util::stream::{self, StreamExt};
+
+/// The maximum number of writes in one commit
+const MAX_BATCH_SIZE: usize = 500;
+
+/// Options for [`delete_recursive_with_options()`] and [`delete_recursive_with_options_async()`]
+#[derive(Clone, Debug)]
+pub struct DeleteRecursiveOptions {
+    /// The number of documents that are deleted in one commit. At most 500. Defaults to 500.
+    pub batch_size: usize,
+    /// The maximum number of commits that are sent at the same time. Defaults to 4.
+    pub concurrency: usize,
+}
+
+impl Default for DeleteRecursiveOptions {
+    fn default() -> Self {
+        DeleteRecursiveOptions {
+            batch_size: MAX_BATCH_SIZE,
+            concurrency: 4,
+        }
+    }
+}
+
+/// A failed commit of a recursive delete
+#[derive(Debug)]
+pub struct DeleteFailure {
+    /// The absolute names of the documents of the failed commit
+    pub documents: Vec<String>,
+    /// The reason
+    pub error: FirebaseError,
+}
+
+/// The progress and the final result of a recursive delete
+#[derive(Debug, Default)]
+pub struct DeleteReport {
+    /// The number of deleted documents
+    pub deleted: usize,
+    /// The failed commits. The documents of those commits still exist.
+    pub failures: Vec<DeleteFailure>,
+}
+
+impl DeleteReport {
+    /// Returns true if all documents have been deleted
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+///
+/// Deletes a document or a whole collection, including all sub-collections.
+///
+/// Sub-collections are discovered with [`list_collection_ids()`] and their documents with [`list_documents()`],
+/// including missing documents that only exist as parents of sub-collections.
+/// The documents are deleted in batched commits, see [`DeleteRecursiveOptions`].
+///
+/// A failed commit does not stop the deletion. The failures are part of the returned [`DeleteReport`].
+/// An error is returned if the documents or collections cannot be listed.
+/// It is safe to run the deletion again.
+///
+/// Example:
+/// ```no_run
+/// use firestore_db_and_auth::documents;
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let report = documents::delete_recursive(&session, "tests")?;
+/// println!("Deleted {} documents", report.deleted);
+/// for failure in report.failures {
+///     println!("Failed to delete {:?}: {}", failure.documents, failure.error);
+/// }
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'path' The relative path of a document or a collection, for example "my_collection/document_id" or "my_collection"
+pub fn delete_recursive(auth: &impl FirebaseAuthBearer, path: &str) -> Result<DeleteReport> {
+    delete_recursive_with_options(auth, path, &DeleteRecursiveOptions::default(), |_| {})
+}
+
+///
+/// Deletes a document or a whole collection, including all sub-collections. See [`delete_recursive()`].
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'path' The relative path of a document or a collection, for example "my_collection/document_id" or "my_collection"
+/// * 'options' The batch size and the number of concurrent commits
+/// * 'progress' Called with the intermediate report after each round of commits
+pub fn delete_recursive_with_options(
+    auth: &impl FirebaseAuthBearer,
+    path: &str,
+    options: &DeleteRecursiveOptions,
+    progress: impl FnMut(&DeleteReport),
+) -> Result<DeleteReport> {
+    let mut deleter = Deleter::new(auth.project_id(), auth.database_id(), options, progress);
+
+    let mut collections = Vec::new();
+    match DeleteTarget::new(path)? {
+        DeleteTarget::Collection(collection) => collections.push(collection),
+        DeleteTarget::Document(document) => {
+            collections.extend(sub_collections(auth, &document)?);
+            deleter.push(firebase_document_name(
+                auth.project_id(),
+                auth.database_id(),
+                document.path(),
+            ));
+        }
+    }
+
+    while let Some(collection) = collections.pop() {
+        for document in list_documents(auth, collection.path(), name_only_options()) {
+            let document = document?;
+            collections.extend(sub_collections(auth, &DocumentRef::from_name(&document.name)?)?);
+            if deleter.push(document.name) {
+                deleter.commit_round(auth);
+            }
+        }
+    }
+    deleter.commit_round(auth);
+
+    Ok(deleter.report)
+}
+
+///
+/// Deletes a document or a whole collection, including all sub-collections. See [`delete_recursive()`].
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'path' The relative path of a document or a collection, for example "my_collection/document_id" or "my_collection"
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn delete_recursive_async(
+    auth: &mut (impl FirebaseAuthBearerAsync + Send),
+    path: &str,
+) -> Result<DeleteReport> {
+    delete_recursive_with_options_async(auth, path, &DeleteRecursiveOptions::default(), |_| {}).await
+}
+
+///
+/// Deletes a document or a whole collection, including all sub-collections. See [`delete_recursive()`].
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'path' The relative path of a document or a collection, for example "my_collection/document_id" or "my_collection"
+/// * 'options' The batch size and the number of concurrent commits
+/// * 'progress' Called with the intermediate report after each round of commits
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn delete_recursive_with_options_async(
+    auth: &mut (impl FirebaseAuthBearerAsync + Send),
+    path: &str,
+    options: &DeleteRecursiveOptions,
+    progress: impl FnMut(&DeleteReport),
+) -> Result<DeleteReport> {
+    let mut deleter = Deleter::new(auth.project_id(), auth.database_id(), options, progress);
+
+    let mut collections = Vec::new();
+    match DeleteTarget::new(path)? {
+        DeleteTarget::Collection(collection) => collections.push(collection),
+        DeleteTarget::Document(document) => {
+            collections.extend(sub_collections_async(auth, &document).await?);
+            deleter.push(firebase_document_name(
+                auth.project_id(),
+                auth.database_id(),
+                document.path(),
+            ));
+        }
+    }
+
+    let params = name_only_options().query_params();
+    while let Some(collection) = collections.pop() {
+        let url = firebase_url(auth.project_id(), auth.database_id(), collection.path());
+        let mut page_token = None;
+        loop {
+            let (_, page) = get_new_data_async(
+                &mut *auth,
+                collection.path().to_owned(),
+                url.clone(),
+                params.clone(),
+                page_token,
+            )
+            .await;
+            let page = page?;
+            for document in page.documents.unwrap_or_default() {
+                collections.extend(sub_collections_async(auth, &DocumentRef::from_name(&document.name)?).await?);
+                if deleter.push(document.name) {
+                    deleter.commit_round_async(auth).await;
+                }
+            }
+            page_token = match page.next_page_token {
+                Some(page_token) => Some(page_token),
+                None => break,
+            };
+        }
+    }
+    deleter.commit_round_async(auth).await;
+
+    Ok(deleter.report)
+}
+
+/// A validated document or collection path
+enum DeleteTarget {
+    Document(DocumentRef),
+    Collection(CollectionRef),
+}
+
+impl DeleteTarget {
+    fn new(path: &str) -> Result<Self> {
+        match DocumentRef::new(path) {
+            Ok(document) => Ok(DeleteTarget::Document(document)),
+            Err(_) => Ok(DeleteTarget::Collection(CollectionRef::new(path)?)),
+        }
+    }
+}
+
+/// Lists missing documents as well, because their sub-collections have to be deleted.
+/// The mask only contains the document name, so that no fields are transferred.
+fn name_only_options() -> ListOptions {
+    ListOptions {
+        show_missing: true,
+        mask: vec!["__name__".to_owned()],
+        ..Default::default()
+    }
+}
+
+fn sub_collections(auth: &impl FirebaseAuthBearer, document: &DocumentRef) -> Result<Vec<CollectionRef>> {
+    list_collection_ids(auth, Some(document.path()))
+        .map(|collection_id| document.collection(&collection_id?))
+        .collect()
+}
+
+async fn sub_collections_async(
+    auth: &mut (impl FirebaseAuthBearerAsync + Send),
+    document: &DocumentRef,
+) -> Result<Vec<CollectionRef>> {
+    let url = firebase_url_method(
+        auth.project_id(),
+        auth.database_id(),
+        Some(document.path()),
+        "listCollectionIds",
+    );
+    let mut collections = Vec::new();
+    let mut page_token = None;
+    loop {
+        let (_, page) =
+            get_collection_ids_async(&mut *auth, url.clone(), Some(document.path().to_owned()), page_token).await;
+        let page = page?;
+        for collection_id in page.collection_ids.unwrap_or_default() {
+            collections.push(document.collection(&collection_id)?);
+        }
+        page_token = match page.next_page_token {
+            Some(page_token) => Some(page_token),
+            None => return Ok(collections),
+        };
+    }
+}
+
+/// Collects document names and deletes them in rounds of concurrent, batched commits
+struct Deleter<P> {
+    url: String,
+    batch_size: usize,
+    concurrency: usize,
+    pending: Vec<String>,
+    report: DeleteReport,
+    progress: P,
+}
+
+impl<P: FnMut(&DeleteReport)> Deleter<P> {
+    fn new(project_id: &str, database_id: &str, options: &DeleteRecursiveOptions, progress: P) -> Self {
+        Deleter {
+            url: firebase_url_method(project_id, database_id, None, "commit"),
+            batch_size: options.batch_size.clamp(1, MAX_BATCH_SIZE),
+            concurrency: options.concurrency.max(1),
+            pending: Vec::new(),
+            report: DeleteReport::default(),
+            progress,
+        }
+    }
+
+    /// Adds a document name. Returns true if enough documents are pending for a round of commits.
+    fn push(&mut self, document_name: String) -> bool {
+        self.pending.push(document_name);
+        self.pending.len() >= self.batch_size * self.concurrency
+    }
+
+    fn take_batches(&mut self) -> Vec<Vec<String>> {
+        let mut batches = Vec::new();
+        let mut pending = std::mem::take(&mut self.pending).into_iter().peekable();
+        while pending.peek().is_some() {
+            batches.push(pending.by_ref().take(self.batch_size).collect());
+        }
+        batches
+    }
+
+    fn record(&mut self, results: Vec<(Vec<String>, Result<()>)>) {
+        for (documents, result) in results {
+            match result {
+                Ok(()) => self.report.deleted += documents.len(),
+                Err(error) => self.report.failures.push(DeleteFailure { documents, error }),
+            }
+        }
+        (self.progress)(&self.report);
+    }
+
+    fn commit_round(&mut self, auth: &impl FirebaseAuthBearer) {
+        let batches = self.take_batches();
+        if batches.is_empty() {
+            return;
+        }
+        let token = auth.access_token();
+        let client = auth.client();
+        let url = &self.url;
+        let results = std::thread::scope(|scope| {
+            let handles: Vec<_> = batches
+                .into_iter()
+                .map(|batch| {
+                    let token = &token;
+                    scope.spawn(move || {
+                        let result = commit_deletes(client, url, token, &batch);
+                        (batch, result)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("A delete commit panicked"))
+                .collect()
+        });
+        self.record(results);
+    }
+
+    async fn commit_round_async(&mut self, auth: &mut impl FirebaseAuthBearerAsync) {
+        let batches = self.take_batches();
+        if batches.is_empty() {
+            return;
+        }
+        let token = auth.access_token().await;
+        let client = auth.client_async();
+        let url = &self.url;
+        let token = &token;
+        let results = stream::iter(batches)
+            .map(|batch| async move {
+                let result = commit_deletes_async(client, url, token, &batch).await;
+                (batch, result)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        self.record(results);
+    }
+}
+
+fn delete_request(document_names: &[String]) -> dto::CommitRequest {
+    dto::CommitRequest {
+        writes: Some(
+            document_names
+                .iter()
+                .map(|name| dto::Write {
+                    delete: Some(name.to_owned()),
+                    ..Default::default()
+                })
+                .collect(),
+        ),
+        transaction: None,
+    }
+}
+
+fn commit_deletes(client: &reqwest::blocking::Client, url: &str, token: &str, document_names: &[String]) -> Result<()> {
+    let resp = client
+        .post(url)
+        .bearer_auth(token)
+        .json(&delete_request(document_names))
+        .send()?;
+
+    extract_google_api_error(resp, || document_names.join(","))?;
+    Ok(())
+}
+
+async fn commit_deletes_async(
+    client: &reqwest::Client,
+    url: &str,
+    token: &str,
+    document_names: &[String],
+) -> Result<()> {
+    let resp = client
+        .post(url)
+        .bearer_auth(token)
+        .json(&delete_request(document_names))
+        .send()
+        .await?;
+
+    extract_google_api_error_async(resp, || document_names.join(",")).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_batches_pending_deletes() {
+        let options = DeleteRecursiveOptions {
+            batch_size: 2,
+            concurrency: 2,
+        };
+        let mut rounds = 0;
+        let mut deleter = Deleter::new("p", DEFAULT_DATABASE_ID, &options, |_| rounds += 1);
+        assert!(!deleter.push("a".to_owned()));
+        assert!(!deleter.push("b".to_owned()));
+        assert!(!deleter.push("c".to_owned()));
+        assert!(deleter.push("d".to_owned()));
+        deleter.push("e".to_owned());
+
+        let batches = deleter.take_batches();
+        assert_eq!(batches, vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+        assert!(deleter.take_batches().is_empty());
+
+        let results = vec![
+            (batches[0].clone(), Ok(())),
+            (batches[1].clone(), Err(FirebaseError::Generic("failed"))),
+            (batches[2].clone(), Ok(())),
+        ];
+        deleter.record(results);
+        assert_eq!(deleter.report.deleted, 3);
+        assert_eq!(deleter.report.failures[0].documents, vec!["c", "d"]);
+        assert!(!deleter.report.is_complete());
+        drop(deleter);
+        assert_eq!(rounds, 1);
+    }
+
+    #[test]
+    fn it_deletes_documents_and_collections() {
+        assert!(matches!(DeleteTarget::new("users"), Ok(DeleteTarget::Collection(_))));
+        assert!(matches!(
+            DeleteTarget::new("users/alice"),
+            Ok(DeleteTarget::Document(_))
+        ));
+        assert!(DeleteTarget::new("users//alice").is_err());
+        let request = serde_json::to_value(delete_request(&[
+            "projects/p/databases/(default)/documents/a/b".to_owned()
+        ]))
+        .unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({"writes": [{"delete": "projects/p/databases/(default)/documents/a/b"}]})
+        );
+    }
+}
@@ -1,11 +1,78 @@
+use super::pager::{page_request, AsyncPager, Pager};
 use super::*;
 use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
-use async_stream::try_stream;
 use futures_core::stream::Stream;
-use futures_util::{pin_mut, stream::StreamExt};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+/// Options for [`list_with_options()`] and [`list_documents()`] and their async variants.
+///
+/// Example:
+/// ```
+/// use firestore_db_and_auth::{documents::ListOptions, dto};
+///
+/// let options = ListOptions {
+///     page_size: Some(50),
+///     order_by: vec![("year".to_owned(), dto::Direction::DESCENDING)],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ListOptions {
+    /// The maximum number of documents per page. Pages are fetched lazily while iterating.
+    pub page_size: Option<u32>,
+    /// The fields to order by, for example `("year", dto::Direction::DESCENDING)`.
+    /// Documents are ordered by their name if empty.
+    pub order_by: Vec<(String, dto::Direction)>,
+    /// Also list missing documents. A document is missing if it does not exist, but has sub-documents.
+    /// Missing documents only have a name and no fields, so use [`list_documents()`] to list them.
+    pub show_missing: bool,
+    /// The field paths to return. All fields are returned if empty.
+    pub mask: Vec<String>,
+    /// List the documents as they were at the given time, within the last hour
+    pub read_time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ListOptions {
+    /// Options that only return the given field paths
+    fn with_mask(mask: &[&str]) -> Self {
+        ListOptions {
+            mask: mask.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// The url query parameters of these options
+    pub(crate) fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params: Vec<(&'static str, String)> = Vec::new();
+        if let Some(page_size) = self.page_size {
+            params.push(("pageSize", page_size.to_string()));
+        }
+        if !self.order_by.is_empty() {
+            let order_by: Vec<String> = self
+                .order_by
+                .iter()
+                .map(|(field, direction)| match direction {
+                    dto::Direction::DESCENDING => format!("{} desc", field),
+                    _ => field.to_owned(),
+                })
+                .collect();
+            params.push(("orderBy", order_by.join(",")));
+        }
+        if self.show_missing {
+            params.push(("showMissing", "true".to_owned()));
+        }
+        params.extend(self.mask.iter().map(|f| ("mask.fieldPaths", f.to_owned())));
+        if let Some(read_time) = self.read_time {
+            params.push((
+                "readTime",
+                read_time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            ));
+        }
+        params
+    }
+}
+
 /// List all documents of a given collection.
 ///
 /// Please note that this API acts as an iterator of same-like documents.
@@ -18,9 +85,9 @@ use std::task::{Context, Poll};
 /// struct DemoDTO { a_string: String, an_int: u32, }
 ///
 /// use firestore_db_and_auth::documents;
-/// # use firestore_db_and_auth::{credentials::Credentials, ServiceSession, errors::Result};
+/// # use firestore_db_and_auth::BlockingServiceSession;
 /// # use firestore_db_and_auth::credentials::doctest_credentials;
-/// # let session = ServiceSession::new(doctest_credentials())?;
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
 ///
 /// let values: documents::List<DemoDTO, _> = documents::list(&session, "tests");
 /// for doc_result in values {
@@ -36,49 +103,237 @@ use std::task::{Context, Poll};
 /// ## Arguments
 /// * 'auth' The authentication token
 /// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
-pub fn list<T, BEARER>(auth: &BEARER, collection_id: impl Into<String>) -> List<T, BEARER>
+pub fn list<T, BEARER>(auth: &BEARER, collection_id: impl Into<String>) -> List<'_, T, BEARER>
+where
+    BEARER: FirebaseAuthBearer,
+{
+    list_with_options(auth, collection_id, ListOptions::default())
+}
+
+/// List all documents of a given collection, but only fetch the given fields.
+///
+/// The document type `T` is usually a struct with a subset of the document fields.
+/// Use this if your documents are big and you only need some fields, for example for an overview.
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
+/// * 'mask' The field paths to return. Fields that are not part of the mask are not transferred.
+pub fn list_with_mask<'a, T, BEARER>(
+    auth: &'a BEARER,
+    collection_id: impl Into<String>,
+    mask: &[&str],
+) -> List<'a, T, BEARER>
+where
+    BEARER: FirebaseAuthBearer,
+{
+    let options = ListOptions::with_mask(mask);
+    list_with_options(auth, collection_id, options)
+}
+
+/// List all documents of a given collection with the given page size, order, field mask or read time.
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
+/// * 'options' The list options
+pub fn list_with_options<T, BEARER>(
+    auth: &BEARER,
+    collection_id: impl Into<String>,
+    options: ListOptions,
+) -> List<'_, T, BEARER>
 where
     BEARER: FirebaseAuthBearer,
 {
-    let collection_id = collection_id.into();
     List {
-        url: firebase_url(auth.project_id(), &collection_id),
-        auth,
-        next_page_token: None,
-        documents: vec![],
-        current: 0,
-        done: false,
-        collection_id,
+        documents: list_documents(auth, collection_id, options),
         phantom: std::marker::PhantomData,
     }
 }
 
-/// THIS IS A NON-BLOCKING OPERATION
+/// List the raw documents of a given collection.
+///
+/// In contrast to [`list()`], the documents are not deserialized and keep their fields.
+/// Use this for collections with documents of different types, or to list missing documents,
+/// see [`ListOptions::show_missing`].
+///
+/// Example:
+/// ```no_run
+/// use firestore_db_and_auth::documents::{self, ListOptions};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let options = ListOptions { show_missing: true, ..Default::default() };
+/// for document in documents::list_documents(&session, "tests", options) {
+///     let document = document?;
+///     if document.fields.is_none() && document.create_time.is_none() {
+///         println!("{} is missing but has sub-collections", document.name);
+///     }
+/// }
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
 /// ## Arguments
 /// * 'auth' The authentication token
 /// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
-pub fn list_async<T: Clone, BEARER>(auth: BEARER, collection_id: impl Into<String>) -> AsyncList<T, BEARER>
+/// * 'options' The list options
+pub fn list_documents<BEARER>(
+    auth: &BEARER,
+    collection_id: impl Into<String>,
+    options: ListOptions,
+) -> DocumentList<'_, BEARER>
 where
-    BEARER: FirebaseAuthBearerAsync + Clone,
+    BEARER: FirebaseAuthBearer,
 {
     let collection_id = collection_id.into();
+    let url = firebase_url(auth.project_id(), auth.database_id(), &collection_id);
+    let params = options.query_params();
+    DocumentList {
+        pager: Pager::new(move |page_token| {
+            get_new_data(&collection_id, &url, &params, page_token.as_deref(), auth).map(Into::into)
+        }),
+        phantom: std::marker::PhantomData,
+    }
+}
+
+/// List all documents of a given collection as a [`Stream`].
+/// Pages are fetched lazily, when the documents of the previous page have been consumed.
+///
+/// The stream takes ownership of the session and hands it to each page request.
+///
+/// Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct DemoDTO { a_string: String, an_int: u32, }
+///
+/// use firestore_db_and_auth::documents;
+/// use futures_util::stream::StreamExt;
+/// # use firestore_db_and_auth::{AsyncServiceSession, credentials::doctest_credentials};
+/// # async fn run() -> firestore_db_and_auth::errors::Result<()> {
+/// # let session = AsyncServiceSession::new(doctest_credentials())?;
+///
+/// let mut values = documents::list_async::<DemoDTO, _>(session, "tests");
+/// while let Some(doc_result) = values.next().await {
+///     let (doc, _metadata) = doc_result?;
+///     println!("{:?}", doc);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub fn list_async<T, BEARER>(auth: BEARER, collection_id: impl Into<String>) -> AsyncList<T, BEARER>
+where
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
+{
+    list_with_options_async(auth, collection_id, ListOptions::default())
+}
+
+/// List all documents of a given collection, but only fetch the given fields.
+/// See [`list_with_mask`].
+///
+/// THIS IS A NON-BLOCKING OPERATION
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
+/// * 'mask' The field paths to return. Fields that are not part of the mask are not transferred.
+pub fn list_with_mask_async<T, BEARER>(
+    auth: BEARER,
+    collection_id: impl Into<String>,
+    mask: &[&str],
+) -> AsyncList<T, BEARER>
+where
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
+{
+    let options = ListOptions::with_mask(mask);
+    list_with_options_async(auth, collection_id, options)
+}
+
+/// List all documents of a given collection with the given page size, order, field mask or read time.
+/// See [`list_with_options`].
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
+/// * 'options' The list options
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub fn list_with_options_async<T, BEARER>(
+    auth: BEARER,
+    collection_id: impl Into<String>,
+    options: ListOptions,
+) -> AsyncList<T, BEARER>
+where
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
+{
     AsyncList {
-        url: firebase_url(auth.project_id(), &collection_id),
-        auth: auth.clone(),
-        next_page_token: None,
-        documents: vec![],
-        current: 0,
-        done: false,
-        collection_id,
+        documents: list_documents_async(auth, collection_id, options),
         phantom: std::marker::PhantomData,
     }
 }
 
+/// List the raw documents of a given collection. See [`list_documents`].
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collection_id' The document path / collection; For example "my_collection" or "a/nested/collection"
+/// * 'options' The list options
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub fn list_documents_async<BEARER>(
+    auth: BEARER,
+    collection_id: impl Into<String>,
+    options: ListOptions,
+) -> AsyncDocumentList<BEARER>
+where
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
+{
+    let collection_id = collection_id.into();
+    let url = firebase_url(auth.project_id(), auth.database_id(), &collection_id);
+    let params = options.query_params();
+    AsyncDocumentList {
+        pager: AsyncPager::new(auth, move |auth, page_token| {
+            page_request(get_new_data_async(
+                auth,
+                collection_id.clone(),
+                url.clone(),
+                params.clone(),
+                page_token,
+            ))
+        }),
+    }
+}
+
+/// The query parameters of a page request: The list options and the page token
+#[inline]
+fn page_query_params<'a>(
+    params: &'a [(&'static str, String)],
+    page_token: Option<&'a str>,
+) -> Vec<(&'static str, &'a str)> {
+    let mut query: Vec<(&'static str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    if let Some(page_token) = page_token {
+        query.push(("pageToken", page_token));
+    }
+    query
+}
+
 #[inline]
-fn get_new_data(collection_id: &str, url: &str, auth: &impl FirebaseAuthBearer) -> Result<dto::ListDocumentsResponse> {
+fn get_new_data(
+    collection_id: &str,
+    url: &str,
+    params: &[(&'static str, String)],
+    page_token: Option<&str>,
+    auth: &impl FirebaseAuthBearer,
+) -> Result<dto::ListDocumentsResponse> {
     let resp = auth
         .client()
         .get(url)
+        .query(&page_query_params(params, page_token))
         .bearer_auth(auth.access_token().to_owned())
         .send()?;
 
@@ -88,59 +343,84 @@ fn get_new_data(collection_id: &str, url: &str, auth: &impl FirebaseAuthBearer)
     Ok(json)
 }
 
-#[inline]
-fn get_new_data_async(
+/// Fetches one page and returns the session, so that it can be used for the next page
+pub(crate) async fn get_new_data_async<BEARER: FirebaseAuthBearerAsync>(
+    mut auth: BEARER,
     collection_id: String,
     url: String,
-    mut auth: impl FirebaseAuthBearerAsync,
-) -> impl Stream<Item = Result<dto::ListDocumentsResponse>> {
-    try_stream! {
+    params: Vec<(&'static str, String)>,
+    page_token: Option<String>,
+) -> (BEARER, Result<dto::ListDocumentsResponse>) {
+    let result = async {
         let resp = auth
             .client_async()
-            .get(url)
+            .get(&url)
+            .query(&page_query_params(&params, page_token.as_deref()))
             .bearer_auth(auth.access_token().await.to_string())
             .send()
             .await?;
         let resp = extract_google_api_error_async(resp, || collection_id.to_owned()).await?;
 
         let json: dto::ListDocumentsResponse = resp.json().await?;
-        yield json
+        Ok(json)
     }
+    .await;
+    (auth, result)
+}
+
+/// Deserializes the document and returns it together with its metadata
+fn document_with_metadata<T>(doc: dto::Document) -> Result<(T, dto::Document)>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let pod = document_to_pod(&doc)?;
+    Ok((pod, dto::Document { fields: None, ..doc }))
 }
 
 /// This type is returned as a result by [`list()`].
 /// Use it as an iterator. The paging API is used internally and new pages are fetched lazily.
 ///
 /// Please note that this API acts as an iterator of same-like documents.
-/// This type is not suitable if you want to list documents of different types.
+/// Use [`list_documents()`] to list documents of different types.
 pub struct List<'a, T, BEARER> {
-    auth: &'a BEARER,
-    next_page_token: Option<String>,
-    documents: Vec<dto::Document>,
-    current: usize,
-    done: bool,
-    url: String,
-    collection_id: String,
+    documents: DocumentList<'a, BEARER>,
     phantom: std::marker::PhantomData<T>,
 }
 
+/// This type is returned as a result by [`list_documents()`].
+/// Use it as an iterator of raw documents. The paging API is used internally and new pages are fetched lazily.
+pub struct DocumentList<'a, BEARER> {
+    pager: Pager<'a, dto::Document>,
+    phantom: std::marker::PhantomData<&'a BEARER>,
+}
+
 /// This type is returned as a result by [`list_async()`].
-/// Use it as an iterator. The paging API is used internally and new pages are fetched lazily.
+/// Use it as a [`Stream`]. The paging API is used internally and new pages are fetched lazily.
 ///
-/// Please note that this API acts as an iterator of same-like documents.
-/// This type is not suitable if you want to list documents of different types.
-#[derive(Clone, Debug)]
-pub struct AsyncList<T: Clone, BEARER> {
-    auth: BEARER,
-    next_page_token: Option<String>,
-    documents: Vec<dto::Document>,
-    current: usize,
-    done: bool,
-    url: String,
-    collection_id: String,
+/// Please note that this API acts as a stream of same-like documents.
+/// Use [`list_documents_async()`] to list documents of different types.
+pub struct AsyncList<T, BEARER> {
+    documents: AsyncDocumentList<BEARER>,
     phantom: std::marker::PhantomData<T>,
 }
 
+/// This type is returned as a result by [`list_documents_async()`].
+/// Use it as a [`Stream`] of raw documents. The paging API is used internally and new pages are fetched lazily.
+pub struct AsyncDocumentList<BEARER> {
+    pager: AsyncPager<BEARER, dto::Document>,
+}
+
+impl<'a, BEARER> Iterator for DocumentList<'a, BEARER>
+where
+    BEARER: FirebaseAuthBearer,
+{
+    type Item = Result<dto::Document>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pager.next()
+    }
+}
+
 impl<'a, T, BEARER> Iterator for List<'a, T, BEARER>
 where
     for<'b> T: Deserialize<'b>,
@@ -149,121 +429,156 @@ where
     type Item = Result<(T, dto::Document)>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
-            return None;
-        }
-
-        if self.documents.len() <= self.current {
-            let url = match &self.next_page_token {
-                Some(next_page_token) => format!("{}pageToken={}", self.url, next_page_token),
-                None => self.url.clone(),
-            };
-
-            let result = get_new_data(&self.collection_id, &url, self.auth);
-            match result {
-                Err(e) => {
-                    self.done = true;
-                    return Some(Err(e));
-                }
-                Ok(v) => match v.documents {
-                    None => return None,
-                    Some(documents) => {
-                        self.documents = documents;
-                        self.current = 0;
-                        self.next_page_token = v.next_page_token;
-                    }
-                },
-            };
-        }
+        self.documents.next().map(|doc| doc.and_then(document_with_metadata))
+    }
+}
 
-        let doc = self.documents.get(self.current).unwrap();
+impl<T, BEARER> Unpin for AsyncList<T, BEARER> {}
 
-        self.current += 1;
-        if self.documents.len() <= self.current && self.next_page_token.is_none() {
-            self.done = true;
-        }
+impl<BEARER> Stream for AsyncDocumentList<BEARER>
+where
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
+{
+    type Item = Result<dto::Document>;
 
-        let result = document_to_pod(doc);
-        match result {
-            Err(e) => Some(Err(e)),
-            Ok(pod) => Some(Ok((
-                pod,
-                dto::Document {
-                    update_time: doc.update_time.clone(),
-                    create_time: doc.create_time.clone(),
-                    name: doc.name.clone(),
-                    fields: None,
-                },
-            ))),
-        }
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().pager).poll_next(cx)
     }
 }
 
 impl<T, BEARER> Stream for AsyncList<T, BEARER>
 where
-    for<'b> T: Deserialize<'b> + Clone,
-    BEARER: FirebaseAuthBearerAsync + Clone,
+    for<'b> T: Deserialize<'b>,
+    BEARER: FirebaseAuthBearerAsync + Send + 'static,
 {
     type Item = Result<(T, dto::Document)>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut s = (*self).clone();
-        if s.done {
-            return Poll::Ready(None);
+        let this = self.get_mut();
+        match Pin::new(&mut this.documents).poll_next(cx) {
+            Poll::Ready(doc) => Poll::Ready(doc.map(|doc| doc.and_then(document_with_metadata))),
+            Poll::Pending => Poll::Pending,
         }
+    }
+}
 
-        if s.documents.len() <= s.current {
-            let url = match &s.next_page_token {
-                Some(next_page_token) => format!("{}pageToken={}", s.url, next_page_token),
-                None => s.url.clone(),
-            };
-
-            let result = get_new_data_async(s.collection_id.clone(), url.clone(), s.auth.clone());
-            pin_mut!(result);
-            let res = result.poll_next_unpin(cx);
-            match res {
-                Poll::Ready(item) => match item {
-                    None => {
-                        return Poll::Ready(None);
-                    }
-                    Some(result) => match result {
-                        Ok(v) => match v.documents {
-                            None => return Poll::Ready(None),
-                            Some(documents) => {
-                                s.documents = documents;
-                                s.current = 0;
-                                s.next_page_token = v.next_page_token;
-                            }
-                        },
-                        Err(e) => {
-                            s.done = true;
-                            return Poll::Ready(Some(Err(e)));
-                        }
-                    },
-                },
-                Poll::Pending => return Poll::Pending,
-            }
+#[cfg(test)]
+mod tests {
+    use super::super::pager::Page;
+    use super::*;
+
+    #[test]
+    fn it_streams_without_clone_bounds() {
+        #[derive(Deserialize)]
+        struct NotClone {
+            _a: String,
         }
+        fn assert_stream<S: Stream + Send + Unpin>(_: &S) {}
 
-        let doc = s.documents.get(s.current).unwrap();
+        let list = list_async::<NotClone, _>(crate::EmulatorOwnerSession::new("p"), "tests");
+        assert_stream(&list);
+        assert!(list.documents.pager.auth.is_some() && list.documents.pager.request.is_none());
+    }
 
-        s.current += 1;
-        if s.documents.len() <= s.current && s.next_page_token.is_none() {
-            s.done = true;
-        }
+    /// Two pages with the names of their documents, linked by the page token "page2"
+    fn fake_page(page_token: Option<String>) -> Result<Page<dto::Document>> {
+        let (names, next_page_token) = match page_token.as_deref() {
+            None => (vec!["a", "b"], Some("page2".to_owned())),
+            Some("page2") => (vec!["c"], None),
+            Some(_) => return Err(FirebaseError::Generic("Unknown page token")),
+        };
+        let items = names
+            .into_iter()
+            .map(|name| dto::Document {
+                name: name.to_owned(),
+                ..Default::default()
+            })
+            .collect();
+        Ok(Page { items, next_page_token })
+    }
+
+    #[test]
+    fn it_lists_documents_of_all_pages() {
+        use futures_util::{FutureExt, StreamExt};
+        use std::sync::{Arc, Mutex};
 
-        let result = document_to_pod(doc);
-        match result {
-            Err(e) => Poll::Ready(Some(Err(e))),
-            Ok(pod) => Poll::Ready(Some(Ok((
-                pod,
-                dto::Document {
-                    update_time: doc.update_time.clone(),
-                    create_time: doc.create_time.clone(),
-                    name: doc.name.clone(),
-                    fields: None,
-                },
-            )))),
+        let mut page_tokens = Vec::new();
+        let list: DocumentList<'_, crate::EmulatorOwnerSession> = DocumentList {
+            pager: Pager::new(|page_token| {
+                page_tokens.push(page_token.clone());
+                fake_page(page_token)
+            }),
+            phantom: std::marker::PhantomData,
+        };
+        let names: Vec<String> = list.map(|doc| doc.unwrap().name).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert_eq!(page_tokens, vec![None, Some("page2".to_owned())]);
+
+        let page_tokens = Arc::new(Mutex::new(Vec::new()));
+        let requested = page_tokens.clone();
+        let mut list = AsyncDocumentList {
+            pager: AsyncPager::new(crate::EmulatorOwnerSession::new("p"), move |auth, page_token| {
+                requested.lock().unwrap().push(page_token.clone());
+                async move { (auth, fake_page(page_token)) }.boxed()
+            }),
+        };
+        let mut names = Vec::new();
+        while let Some(doc) = list.next().now_or_never().expect("The fake pages are ready") {
+            names.push(doc.unwrap().name);
         }
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert_eq!(*page_tokens.lock().unwrap(), vec![None, Some("page2".to_owned())]);
+        // The stream stays ended and does not request the first page again
+        assert!(list.next().now_or_never().unwrap().is_none());
+        assert_eq!(page_tokens.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn it_builds_list_query_params() {
+        let options = ListOptions {
+            page_size: Some(20),
+            order_by: vec![
+                ("year".to_owned(), dto::Direction::DESCENDING),
+                ("name".to_owned(), dto::Direction::ASCENDING),
+            ],
+            show_missing: true,
+            mask: vec!["name".to_owned()],
+            read_time: Some(chrono::DateTime::from_timestamp(1588085571, 0).unwrap()),
+        };
+        let params = options.query_params();
+        assert_eq!(
+            page_query_params(&params, Some("token")),
+            vec![
+                ("pageSize", "20"),
+                ("orderBy", "year desc,name"),
+                ("showMissing", "true"),
+                ("mask.fieldPaths", "name"),
+                ("readTime", "2020-04-28T14:52:51Z"),
+                ("pageToken", "token"),
+            ]
+        );
+        assert!(ListOptions::default().query_params().is_empty());
+    }
+
+    #[test]
+    fn it_builds_list_mask_query_params() -> Result<()> {
+        let zip_code = field_path(&["address", "zip code"]);
+        let params = ListOptions::with_mask(&["name", &zip_code]).query_params();
+        assert_eq!(
+            params,
+            vec![
+                ("mask.fieldPaths", "name".to_owned()),
+                ("mask.fieldPaths", "address.`zip code`".to_owned()),
+            ]
+        );
+        let request = reqwest::blocking::Client::new()
+            .get("https://firestore.googleapis.com/v1/tests")
+            .query(&params)
+            .build()?;
+        assert_eq!(
+            request.url().query(),
+            Some("mask.fieldPaths=name&mask.fieldPaths=address.%60zip+code%60")
+        );
+        Ok(())
     }
 }
@@ -4,6 +4,7 @@
 //! Please check the root page of this documentation for examples.
 
 use super::dto;
+use super::endpoints::endpoints;
 use super::errors::{extract_google_api_error, FirebaseError, Result};
 use super::firebase_rest_to_rust::{document_to_pod, pod_to_document};
 use super::FirebaseAuthBearer;
@@ -11,17 +12,32 @@ use super::FirebaseAuthBearer;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+mod aggregate;
+mod batch_get;
+mod collection_ids;
 mod delete;
+mod delete_recursive;
 mod list;
+mod pager;
 mod query;
 mod read;
+mod reference;
+mod transaction;
 mod write;
+mod write_batch;
 
+pub use aggregate::*;
+pub use batch_get::*;
+pub use collection_ids::*;
 pub use delete::*;
+pub use delete_recursive::*;
 pub use list::*;
 pub use query::*;
 pub use read::*;
+pub use reference::*;
+pub use transaction::*;
 pub use write::*;
+pub use write_batch::*;
 
 /// An [`Iterator`] implementation that provides a join method
 ///
@@ -51,41 +67,80 @@ pub trait JoinableIterator: Iterator {
 
 impl<'a, VALUE> JoinableIterator for std::collections::hash_map::Keys<'a, String, VALUE> {}
 
+/// The id of the database that is used, if a session does not name another database
+pub const DEFAULT_DATABASE_ID: &str = "(default)";
+
+/// The url of a method like "runQuery" on the documents of the database root or of a parent document
 #[inline]
-fn firebase_url_query(v1: &str) -> String {
-    format!(
-        "https://firestore.googleapis.com/v1/projects/{}/databases/(default)/documents:runQuery",
-        v1
-    )
+fn firebase_url_method(project_id: &str, database_id: &str, parent: Option<&str>, method: &str) -> String {
+    match parent {
+        Some(parent) => format!(
+            "{}/projects/{}/databases/{}/documents/{}:{}",
+            endpoints().firestore,
+            project_id,
+            database_id,
+            parent,
+            method
+        ),
+        None => format!(
+            "{}/projects/{}/databases/{}/documents:{}",
+            endpoints().firestore,
+            project_id,
+            database_id,
+            method
+        ),
+    }
 }
 
 #[inline]
 fn firebase_url_base(v1: &str) -> String {
-    format!("https://firestore.googleapis.com/v1/{}", v1)
+    format!("{}/{}", endpoints().firestore, v1)
 }
 
 #[inline]
-fn firebase_url_extended(v1: &str, v2: &str, v3: &str) -> String {
+fn firebase_url_extended(project_id: &str, database_id: &str, v2: &str, v3: &str) -> String {
     format!(
-        "https://firestore.googleapis.com/v1/projects/{}/databases/(default)/documents/{}/{}",
-        v1, v2, v3
+        "{}/projects/{}/databases/{}/documents/{}/{}",
+        endpoints().firestore,
+        project_id,
+        database_id,
+        v2,
+        v3
     )
 }
 
 #[inline]
-fn firebase_url(v1: &str, v2: &str) -> String {
+fn firebase_url(project_id: &str, database_id: &str, v2: &str) -> String {
     format!(
-        "https://firestore.googleapis.com/v1/projects/{}/databases/(default)/documents/{}?",
-        v1, v2
+        "{}/projects/{}/databases/{}/documents/{}?",
+        endpoints().firestore,
+        project_id,
+        database_id,
+        v2
     )
 }
 
-/// Converts an absolute path like "projects/{PROJECT_ID}/databases/(default)/documents/my_collection/document_id"
+/// The absolute document name like "projects/{PROJECT_ID}/databases/{DATABASE_ID}/documents/my_collection/document_id"
+/// of a relative document path like "my_collection/document_id"
+#[inline]
+fn firebase_document_name(project_id: &str, database_id: &str, path: &str) -> String {
+    format!("projects/{}/databases/{}/documents/{}", project_id, database_id, path)
+}
+
+/// Returns the url query parameters for a document mask, for example `mask.fieldPaths=a&mask.fieldPaths=b`.
+/// Only the given fields will be returned by a read or list call.
+#[inline]
+fn mask_query_params<S: AsRef<str>>(mask: &[S]) -> Vec<(&'static str, &str)> {
+    mask.iter().map(|f| ("mask.fieldPaths", f.as_ref())).collect()
+}
+
+/// Converts an absolute path like "projects/{PROJECT_ID}/databases/{DATABASE_ID}/documents/my_collection/document_id"
 /// into a relative document path like "my_collection/document_id"
 ///
 /// This is usually used to get a suitable path for [`delete()`] or [`delete_async()`].
+/// Prefer [`DocumentRef::from_name`], which validates the name and does not panic.
 pub fn abs_to_rel(path: &str) -> &str {
-    &path[path.find("(default)").unwrap() + 20..]
+    &path[path.find("/documents/").unwrap() + 11..]
 }
 
 #[test]
@@ -94,4 +149,8 @@ fn abs_to_rel_test() {
         abs_to_rel("projects/{PROJECT_ID}/databases/(default)/documents/my_collection/document_id"),
         "my_collection/document_id"
     );
+    assert_eq!(
+        abs_to_rel("projects/{PROJECT_ID}/databases/eu-tenant/documents/my_collection/document_id"),
+        "my_collection/document_id"
+    );
 }
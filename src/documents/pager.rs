@@ -0,0 +1,177 @@
+use super::*;
+use futures_core::stream::Stream;
+use futures_util::future::{BoxFuture, FutureExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// One page of a paginated API call: The items and the token of the next page, if there is one
+pub(crate) struct Page<I> {
+    pub(crate) items: Vec<I>,
+    pub(crate) next_page_token: Option<String>,
+}
+
+impl From<dto::ListDocumentsResponse> for Page<dto::Document> {
+    fn from(response: dto::ListDocumentsResponse) -> Self {
+        Page {
+            items: response.documents.unwrap_or_default(),
+            next_page_token: response.next_page_token,
+        }
+    }
+}
+
+impl From<dto::ListCollectionIdsResponse> for Page<String> {
+    fn from(response: dto::ListCollectionIdsResponse) -> Self {
+        Page {
+            items: response.collection_ids.unwrap_or_default(),
+            next_page_token: response.next_page_token,
+        }
+    }
+}
+
+/// A page request that owns the session and hands it back together with the page
+pub(crate) type PageRequest<S, I> = BoxFuture<'static, (S, Result<Page<I>>)>;
+
+/// The state that is carried over between pages: The buffered items and the next page token
+struct PageState<I> {
+    items: std::vec::IntoIter<I>,
+    next_page_token: Option<String>,
+    done: bool,
+}
+
+impl<I> PageState<I> {
+    fn new() -> Self {
+        PageState {
+            items: Vec::new().into_iter(),
+            next_page_token: None,
+            done: false,
+        }
+    }
+
+    /// The next buffered item, `Some(None)` after the last page, or `None` if the next page must be fetched
+    fn buffered(&mut self) -> Option<Option<I>> {
+        match self.items.next() {
+            Some(item) => Some(Some(item)),
+            None if self.done => Some(None),
+            None => None,
+        }
+    }
+
+    /// Buffers a fetched page. A failed request ends the paging and its error is returned.
+    fn receive(&mut self, page: Result<Page<I>>) -> Option<FirebaseError> {
+        match page {
+            Err(e) => {
+                self.done = true;
+                Some(e)
+            }
+            Ok(page) => {
+                self.done = page.next_page_token.is_none();
+                self.next_page_token = page.next_page_token;
+                self.items = page.items.into_iter();
+                None
+            }
+        }
+    }
+}
+
+/// An iterator over the items of all pages. Pages are fetched lazily with the given function,
+/// which is called with the token of the requested page, `None` for the first page.
+pub(crate) struct Pager<'a, I> {
+    state: PageState<I>,
+    fetch: Box<dyn FnMut(Option<String>) -> Result<Page<I>> + 'a>,
+}
+
+impl<'a, I> Pager<'a, I> {
+    pub(crate) fn new(fetch: impl FnMut(Option<String>) -> Result<Page<I>> + 'a) -> Self {
+        Pager {
+            state: PageState::new(),
+            fetch: Box::new(fetch),
+        }
+    }
+}
+
+impl<'a, I> Iterator for Pager<'a, I> {
+    type Item = Result<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.state.buffered() {
+                return item.map(Ok);
+            }
+            let page = (self.fetch)(self.state.next_page_token.take());
+            if let Some(e) = self.state.receive(page) {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// A [`Stream`] over the items of all pages. Pages are fetched lazily with the given function,
+/// which takes the session and the token of the requested page and returns the session with the page.
+pub(crate) struct AsyncPager<S, I> {
+    /// The session, `None` while a page request owns it
+    pub(crate) auth: Option<S>,
+    /// The page request in flight. It is kept across polls until it is ready.
+    pub(crate) request: Option<PageRequest<S, I>>,
+    state: PageState<I>,
+    fetch: Box<dyn FnMut(S, Option<String>) -> PageRequest<S, I> + Send>,
+}
+
+impl<S, I> AsyncPager<S, I> {
+    pub(crate) fn new(auth: S, fetch: impl FnMut(S, Option<String>) -> PageRequest<S, I> + Send + 'static) -> Self {
+        AsyncPager {
+            auth: Some(auth),
+            request: None,
+            state: PageState::new(),
+            fetch: Box::new(fetch),
+        }
+    }
+}
+
+/// Turns a page request that returns an api response into a [`PageRequest`]
+pub(crate) fn page_request<S, R, I>(
+    request: impl std::future::Future<Output = (S, Result<R>)> + Send + 'static,
+) -> PageRequest<S, I>
+where
+    Page<I>: From<R>,
+{
+    request.map(|(auth, result)| (auth, result.map(Page::from))).boxed()
+}
+
+// No field is structurally pinned: The page request is boxed and the session is moved in and out
+impl<S, I> Unpin for AsyncPager<S, I> {}
+
+impl<S, I> Stream for AsyncPager<S, I> {
+    type Item = Result<I>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.state.buffered() {
+                return Poll::Ready(item.map(Ok));
+            }
+
+            let request = match this.request.as_mut() {
+                Some(request) => request,
+                None => {
+                    let auth = match this.auth.take() {
+                        Some(auth) => auth,
+                        None => return Poll::Ready(None),
+                    };
+                    let page_token = this.state.next_page_token.take();
+                    this.request.insert((this.fetch)(auth, page_token))
+                }
+            };
+
+            let (auth, page) = match request.poll_unpin(cx) {
+                Poll::Ready(response) => response,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.request = None;
+            this.auth = Some(auth);
+
+            if let Some(e) = this.state.receive(page) {
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
 
 use super::*;
+use std::convert::TryFrom;
 use std::vec::IntoIter;
 
 ///
@@ -28,10 +29,12 @@ use std::vec::IntoIter;
 /// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
 /// ```
 ///
+/// Use a [`QueryBuilder`] for queries with more than one filter, ordering, limits or cursors.
+///
 /// ## Arguments
 /// * 'auth' The authentication token
 /// * 'collectionid' The collection id; "my_collection" or "a/nested/collection"
-/// * 'value' The query / filter value. For example "car".
+/// * 'value' The query / filter value. For example "car". Values that Firestore can not store, like integers above `i64::MAX`, are an error.
 /// * 'operator' The query operator. For example "EQUAL".
 /// * 'field' The query / filter field. For example "type".
 pub fn query(
@@ -41,43 +44,10 @@ pub fn query(
     operator: dto::FieldOperator,
     field: &str,
 ) -> Result<Query> {
-    let url = firebase_url_query(auth.project_id());
-    let value = crate::firebase_rest_to_rust::serde_value_to_firebase_value(&value);
-
-    let query_request = dto::RunQueryRequest {
-        structured_query: Some(dto::StructuredQuery {
-            select: Some(dto::Projection { fields: None }),
-            where_: Some(dto::Filter {
-                field_filter: Some(dto::FieldFilter {
-                    value,
-                    op: operator,
-                    field: dto::FieldReference {
-                        field_path: field.to_owned(),
-                    },
-                }),
-                ..Default::default()
-            }),
-            from: Some(vec![dto::CollectionSelector {
-                collection_id: Some(collection_id.to_owned()),
-                ..Default::default()
-            }]),
-            ..Default::default()
-        }),
-        ..Default::default()
-    };
-
-    let resp = auth
-        .client()
-        .post(url)
-        .bearer_auth(auth.access_token().to_owned())
-        .json(&query_request)
-        .send()?;
-
-    let resp = extract_google_api_error(resp, || collection_id.to_owned())?;
-
-    let json: Option<Vec<dto::RunQueryResponse>> = resp.json()?;
-
-    Ok(Query(json.unwrap_or_default().into_iter()))
+    QueryBuilder::new(collection_id)
+        .filter(dto::Filter::field(field, operator, dto::Value::try_from(value)?))
+        .select(Vec::<String>::new())
+        .run(auth)
 }
 
 ///
@@ -108,7 +78,7 @@ pub fn query(
 /// ## Arguments
 /// * 'auth' The authentication token
 /// * 'collectionid' The collection id; "my_collection" or "a/nested/collection"
-/// * 'value' The query / filter value. For example "car".
+/// * 'value' The query / filter value. For example "car". Values that Firestore can not store, like integers above `i64::MAX`, are an error.
 /// * 'operator' The query operator. For example "EQUAL".
 /// * 'field' The query / filter field. For example "type".
 /// THIS IS A NON-BLOCKING OPERATION
@@ -119,28 +89,561 @@ pub async fn query_async(
     operator: dto::FieldOperator,
     field: &str,
 ) -> Result<Query> {
-    let url = firebase_url_query(auth.project_id());
-    let value = crate::firebase_rest_to_rust::serde_value_to_firebase_value(&value);
+    QueryBuilder::new(collection_id)
+        .filter(dto::Filter::field(field, operator, dto::Value::try_from(value)?))
+        .select(Vec::<String>::new())
+        .run_async(auth)
+        .await
+}
 
-    let query_request = dto::RunQueryRequest {
-        structured_query: Some(dto::StructuredQuery {
-            select: Some(dto::Projection { fields: None }),
-            where_: Some(dto::Filter {
-                field_filter: Some(dto::FieldFilter {
-                    value,
-                    op: operator,
-                    field: dto::FieldReference {
-                        field_path: field.to_owned(),
-                    },
-                }),
+///
+/// Queries the database for documents with a field that is (or is not) `null` or `NaN`,
+/// for example all documents in a collection where 'deleted_at' IS_NULL.
+///
+/// Example:
+/// ```no_run
+/// use firestore_db_and_auth::{documents, dto};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let values: documents::Query = documents::query_unary(&session, "tests", dto::UnaryOperator::IS_NULL, "deleted_at")?;
+/// for metadata in values {
+///     println!("id: {}", &metadata.name);
+/// }
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collectionid' The collection id; "my_collection" or "a/nested/collection"
+/// * 'operator' The unary operator. For example "IS_NULL".
+/// * 'field' The query / filter field. For example "deleted_at".
+pub fn query_unary(
+    auth: &impl FirebaseAuthBearer,
+    collection_id: &str,
+    operator: dto::UnaryOperator,
+    field: &str,
+) -> Result<Query> {
+    QueryBuilder::new(collection_id)
+        .filter(dto::Filter::unary(field, operator))
+        .select(Vec::<String>::new())
+        .run(auth)
+}
+
+///
+/// Queries the database for documents with a field that is (or is not) `null` or `NaN`,
+/// for example all documents in a collection where 'deleted_at' IS_NULL.
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collectionid' The collection id; "my_collection" or "a/nested/collection"
+/// * 'operator' The unary operator. For example "IS_NULL".
+/// * 'field' The query / filter field. For example "deleted_at".
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn query_unary_async(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    collection_id: &str,
+    operator: dto::UnaryOperator,
+    field: &str,
+) -> Result<Query> {
+    QueryBuilder::new(collection_id)
+        .filter(dto::Filter::unary(field, operator))
+        .select(Vec::<String>::new())
+        .run_async(auth)
+        .await
+}
+
+/// Queries the database for specific documents and returns the documents themselves,
+/// deserialized into `T`, instead of only the document references.
+///
+/// Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct DemoDTO { a_string: String, an_int: u32, }
+///
+/// use firestore_db_and_auth::{documents, dto};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let values: documents::TypedQuery<DemoDTO> = documents::query_typed(&session, "tests", "Sam Weiss".into(), dto::FieldOperator::EQUAL, "id")?;
+/// for doc_result in values {
+///     // A tuple is returned on success with the document itself and and metadata
+///     // with .name, .create_time, .update_time fields.
+///     let (doc, metadata) = doc_result?;
+///     println!("{}: {:?}", metadata.name, doc);
+/// }
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collectionid' The collection id; "my_collection" or "a/nested/collection"
+/// * 'value' The query / filter value. For example "car". Values that Firestore can not store, like integers above `i64::MAX`, are an error.
+/// * 'operator' The query operator. For example "EQUAL".
+/// * 'field' The query / filter field. For example "type".
+pub fn query_typed<T>(
+    auth: &impl FirebaseAuthBearer,
+    collection_id: &str,
+    value: serde_json::Value,
+    operator: dto::FieldOperator,
+    field: &str,
+) -> Result<TypedQuery<T>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    QueryBuilder::new(collection_id)
+        .filter(dto::Filter::field(field, operator, dto::Value::try_from(value)?))
+        .run_typed(auth)
+}
+
+///
+/// Queries the database for specific documents and returns the documents themselves,
+/// deserialized into `T`, instead of only the document references.
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'collectionid' The collection id; "my_collection" or "a/nested/collection"
+/// * 'value' The query / filter value. For example "car". Values that Firestore can not store, like integers above `i64::MAX`, are an error.
+/// * 'operator' The query operator. For example "EQUAL".
+/// * 'field' The query / filter field. For example "type".
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn query_typed_async<T>(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    collection_id: &str,
+    value: serde_json::Value,
+    operator: dto::FieldOperator,
+    field: &str,
+) -> Result<TypedQuery<T>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    QueryBuilder::new(collection_id)
+        .filter(dto::Filter::field(field, operator, dto::Value::try_from(value)?))
+        .run_typed_async(auth)
+        .await
+}
+
+impl dto::Filter {
+    /// Creates a field filter, for example `type == "car"`.
+    ///
+    /// ## Arguments
+    /// * 'field' The field path. For example "type".
+    /// * 'operator' The query operator. For example "EQUAL".
+    /// * 'value' The filter value. For example "car".
+    pub fn field(field: &str, operator: dto::FieldOperator, value: impl Into<dto::Value>) -> Self {
+        dto::Filter {
+            field_filter: Some(dto::FieldFilter {
+                field: dto::FieldReference {
+                    field_path: field.to_owned(),
+                },
+                op: operator,
+                value: value.into(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a unary filter, for example `IS_NULL` or `IS_NAN` on the given field.
+    pub fn unary(field: &str, operator: dto::UnaryOperator) -> Self {
+        dto::Filter {
+            unary_filter: Some(dto::UnaryFilter {
+                field: dto::FieldReference {
+                    field_path: field.to_owned(),
+                },
+                op: operator,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Combines the given filters. A document must match all of them.
+    pub fn and(filters: impl IntoIterator<Item = dto::Filter>) -> Self {
+        Self::composite(dto::CompositeOperator::AND, filters)
+    }
+
+    /// Combines the given filters. A document must match at least one of them.
+    pub fn or(filters: impl IntoIterator<Item = dto::Filter>) -> Self {
+        Self::composite(dto::CompositeOperator::OR, filters)
+    }
+
+    fn composite(operator: dto::CompositeOperator, filters: impl IntoIterator<Item = dto::Filter>) -> Self {
+        dto::Filter {
+            composite_filter: Some(dto::CompositeFilter {
+                filters: filters.into_iter().collect(),
+                op: operator,
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// A builder for structured queries.
+///
+/// All filters added via [`QueryBuilder::filter`] must match (they are combined with AND).
+/// Use [`dto::Filter::or`] and [`dto::Filter::and`] to nest other combinations.
+///
+/// Example:
+/// ```no_run
+/// use firestore_db_and_auth::{documents::QueryBuilder, dto};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let results = QueryBuilder::new("cars")
+///     .filter(dto::Filter::field("year", dto::FieldOperator::GREATER_THAN_OR_EQUAL, 2015))
+///     .filter(dto::Filter::or(vec![
+///         dto::Filter::field("color", dto::FieldOperator::EQUAL, "red"),
+///         dto::Filter::field("color", dto::FieldOperator::EQUAL, "blue"),
+///     ]))
+///     .order_by("year", dto::Direction::DESCENDING)
+///     .limit(10)
+///     .run(&session)?;
+/// for document in results {
+///     println!("{}", document.name);
+/// }
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// Use [`QueryBuilder::collection_group`] to query all collections with the same id, no matter where they are
+/// nested. For example all "comments" subcollections of all "posts" documents:
+/// ```no_run
+/// use firestore_db_and_auth::{documents::QueryBuilder, dto};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let all_comments = QueryBuilder::collection_group("comments")
+///     .field_filter("author", dto::FieldOperator::EQUAL, "Sam Weiss")
+///     .run(&session)?;
+/// // Only the comments below posts/abc (and their subcollections)
+/// let comments_of_abc = QueryBuilder::collection_group("comments").parent("posts/abc").run(&session)?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+#[derive(Default, Clone, Debug)]
+pub struct QueryBuilder {
+    parent: Option<String>,
+    collection_id: String,
+    all_descendants: bool,
+    filters: Vec<dto::Filter>,
+    order_by: Vec<dto::Order>,
+    start_at: Option<dto::Cursor>,
+    end_at: Option<dto::Cursor>,
+    limit: Option<i32>,
+    offset: Option<i32>,
+    select: Option<Vec<String>>,
+}
+
+impl QueryBuilder {
+    /// Creates a query for the documents of the given collection.
+    ///
+    /// A nested collection like "posts/abc/comments" is queried with "posts/abc" as [`QueryBuilder::parent`].
+    ///
+    /// ## Arguments
+    /// * 'collection_id' The collection id; "my_collection" or "a/nested/collection"
+    pub fn new(collection_id: impl Into<String>) -> Self {
+        let collection_id = collection_id.into();
+        match collection_id.rsplit_once('/') {
+            Some((parent, collection_id)) => QueryBuilder {
+                parent: Some(parent.to_owned()),
+                collection_id: collection_id.to_owned(),
+                ..Default::default()
+            },
+            None => QueryBuilder {
+                collection_id,
                 ..Default::default()
+            },
+        }
+    }
+
+    /// Creates a collection group query. It includes the documents of all collections with the given id,
+    /// no matter where they are nested. Restrict it to the descendants of a document with [`QueryBuilder::parent`].
+    ///
+    /// ## Arguments
+    /// * 'collection_id' The collection id without any path; For example "comments"
+    pub fn collection_group(collection_id: impl Into<String>) -> Self {
+        QueryBuilder {
+            collection_id: collection_id.into(),
+            all_descendants: true,
+            ..Default::default()
+        }
+    }
+
+    /// Only query the collections of the given parent document, instead of the database root.
+    ///
+    /// ## Arguments
+    /// * 'document_path' The relative document path; For example "posts/abc"
+    pub fn parent(mut self, document_path: impl Into<String>) -> Self {
+        self.parent = Some(document_path.into());
+        self
+    }
+
+    /// Adds a filter. Documents must match all added filters.
+    pub fn filter(mut self, filter: dto::Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Adds a field filter, for example `type == "car"`. See [`dto::Filter::field`].
+    pub fn field_filter(self, field: &str, operator: dto::FieldOperator, value: impl Into<dto::Value>) -> Self {
+        self.filter(dto::Filter::field(field, operator, value))
+    }
+
+    /// Adds a unary filter, for example `IS_NULL`. See [`dto::Filter::unary`].
+    pub fn unary_filter(self, field: &str, operator: dto::UnaryOperator) -> Self {
+        self.filter(dto::Filter::unary(field, operator))
+    }
+
+    /// Only return the given fields of the matching documents.
+    ///
+    /// Use this together with [`QueryBuilder::run_typed`] and a type `T` that only
+    /// contains a subset of the document fields. An empty list returns only the document names.
+    pub fn select<S: Into<String>>(mut self, fields: impl IntoIterator<Item = S>) -> Self {
+        self.select = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Orders the results by the given field. Can be called multiple times.
+    pub fn order_by(mut self, field: &str, direction: dto::Direction) -> Self {
+        self.order_by.push(dto::Order {
+            field: Some(dto::FieldReference {
+                field_path: field.to_owned(),
             }),
+            direction: Some(direction),
+        });
+        self
+    }
+
+    /// The maximum number of results to return.
+    pub fn limit(mut self, limit: i32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The number of results to skip.
+    pub fn offset(mut self, offset: i32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Starts the results at a document with the given values of the [`QueryBuilder::order_by`] fields.
+    pub fn start_at(mut self, values: Vec<dto::Value>) -> Self {
+        self.start_at = Some(cursor(values, true));
+        self
+    }
+
+    /// Starts the results directly after a document with the given values of the [`QueryBuilder::order_by`] fields.
+    pub fn start_after(mut self, values: Vec<dto::Value>) -> Self {
+        self.start_at = Some(cursor(values, false));
+        self
+    }
+
+    /// Ends the results at a document with the given values of the [`QueryBuilder::order_by`] fields.
+    pub fn end_at(mut self, values: Vec<dto::Value>) -> Self {
+        self.end_at = Some(cursor(values, false));
+        self
+    }
+
+    /// Ends the results directly before a document with the given values of the [`QueryBuilder::order_by`] fields.
+    pub fn end_before(mut self, values: Vec<dto::Value>) -> Self {
+        self.end_at = Some(cursor(values, true));
+        self
+    }
+
+    /// Returns the structured query for this builder.
+    pub fn build(&self) -> dto::StructuredQuery {
+        let where_ = match self.filters.len() {
+            0 => None,
+            1 => self.filters.first().cloned(),
+            _ => Some(dto::Filter::and(self.filters.clone())),
+        };
+
+        dto::StructuredQuery {
             from: Some(vec![dto::CollectionSelector {
-                collection_id: Some(collection_id.to_owned()),
-                ..Default::default()
+                collection_id: Some(self.collection_id.clone()),
+                all_descendants: match self.all_descendants {
+                    true => Some(true),
+                    false => None,
+                },
             }]),
-            ..Default::default()
-        }),
+            where_,
+            order_by: match self.order_by.is_empty() {
+                true => None,
+                false => Some(self.order_by.clone()),
+            },
+            start_at: self.start_at.clone(),
+            end_at: self.end_at.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            select: self.select.as_ref().map(|fields| dto::Projection {
+                fields: Some(
+                    fields
+                        .iter()
+                        .map(|field| dto::FieldReference {
+                            field_path: field.to_owned(),
+                        })
+                        .collect(),
+                ),
+            }),
+        }
+    }
+
+    /// Returns the structured query, if it passes the checks of [`validate_filter`]
+    pub(crate) fn build_validated(&self) -> Result<dto::StructuredQuery> {
+        let structured_query = self.build();
+        if let Some(filter) = structured_query.where_.as_ref() {
+            validate_filter(filter)?;
+        }
+        Ok(structured_query)
+    }
+
+    /// The parent document path, if any
+    pub(crate) fn parent_path(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// The collection path, used as context for errors
+    pub(crate) fn context(&self) -> String {
+        match self.parent.as_ref() {
+            Some(parent) => format!("{}/{}", parent, self.collection_id),
+            None => self.collection_id.clone(),
+        }
+    }
+
+    /// Executes the query. The resulting [`Query`] iterates over the matching documents.
+    pub fn run(&self, auth: &impl FirebaseAuthBearer) -> Result<Query> {
+        let json = run_query(auth, self)?;
+        Ok(Query(json.into_iter()))
+    }
+
+    /// Executes the query. The resulting [`Query`] iterates over the matching documents.
+    /// THIS IS A NON-BLOCKING OPERATION
+    pub async fn run_async(&self, auth: &mut impl FirebaseAuthBearerAsync) -> Result<Query> {
+        let json = run_query_async(auth, self).await?;
+        Ok(Query(json.into_iter()))
+    }
+
+    /// Executes the query. The resulting [`TypedQuery`] iterates over the matching documents,
+    /// deserialized into `T`.
+    pub fn run_typed<T>(&self, auth: &impl FirebaseAuthBearer) -> Result<TypedQuery<T>>
+    where
+        for<'b> T: Deserialize<'b>,
+    {
+        let json = run_query(auth, self)?;
+        Ok(TypedQuery(json.into_iter(), std::marker::PhantomData))
+    }
+
+    /// Executes the query. The resulting [`TypedQuery`] iterates over the matching documents,
+    /// deserialized into `T`.
+    /// THIS IS A NON-BLOCKING OPERATION
+    pub async fn run_typed_async<T>(&self, auth: &mut impl FirebaseAuthBearerAsync) -> Result<TypedQuery<T>>
+    where
+        for<'b> T: Deserialize<'b>,
+    {
+        let json = run_query_async(auth, self).await?;
+        Ok(TypedQuery(json.into_iter(), std::marker::PhantomData))
+    }
+}
+
+#[inline]
+fn cursor(values: Vec<dto::Value>, before: bool) -> dto::Cursor {
+    dto::Cursor {
+        values: Some(values),
+        before: Some(before),
+    }
+}
+
+/// Checks the value shape rules of the given filter and all nested filters.
+/// Firestore would reject such a query, so it is not sent at all.
+fn validate_filter(filter: &dto::Filter) -> Result<()> {
+    if let Some(field_filter) = filter.field_filter.as_ref() {
+        let max_values = match field_filter.op {
+            dto::FieldOperator::OPERATOR_UNSPECIFIED => {
+                return Err(FirebaseError::InvalidQuery(format!(
+                    "No operator given for field '{}'",
+                    field_filter.field.field_path
+                )))
+            }
+            dto::FieldOperator::IN | dto::FieldOperator::ARRAY_CONTAINS_ANY => 30,
+            dto::FieldOperator::NOT_IN => 10,
+            _ => return Ok(()),
+        };
+        let values = field_filter.value.array_value.as_ref().and_then(|a| a.values.as_ref());
+        match values {
+            Some(values) if !values.is_empty() && values.len() <= max_values => {}
+            _ => {
+                return Err(FirebaseError::InvalidQuery(format!(
+                    "{:?} on field '{}' requires a non-empty array with at most {} values",
+                    field_filter.op, field_filter.field.field_path, max_values
+                )))
+            }
+        }
+    }
+    if let Some(unary_filter) = filter.unary_filter.as_ref() {
+        if let dto::UnaryOperator::OPERATOR_UNSPECIFIED = unary_filter.op {
+            return Err(FirebaseError::InvalidQuery(format!(
+                "No operator given for field '{}'",
+                unary_filter.field.field_path
+            )));
+        }
+    }
+    if let Some(composite_filter) = filter.composite_filter.as_ref() {
+        if composite_filter.filters.is_empty() {
+            return Err(FirebaseError::InvalidQuery(format!(
+                "{:?} requires at least one filter",
+                composite_filter.op
+            )));
+        }
+        for filter in &composite_filter.filters {
+            validate_filter(filter)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_query(auth: &impl FirebaseAuthBearer, query: &QueryBuilder) -> Result<Vec<dto::RunQueryResponse>> {
+    let structured_query = query.build_validated()?;
+
+    let url = firebase_url_method(
+        auth.project_id(),
+        auth.database_id(),
+        query.parent.as_deref(),
+        "runQuery",
+    );
+
+    let query_request = dto::RunQueryRequest {
+        structured_query: Some(structured_query),
+        ..Default::default()
+    };
+
+    let resp = auth
+        .client()
+        .post(url)
+        .bearer_auth(auth.access_token().to_owned())
+        .json(&query_request)
+        .send()?;
+
+    let resp = extract_google_api_error(resp, || query.context())?;
+
+    let json: Option<Vec<dto::RunQueryResponse>> = resp.json()?;
+    Ok(json.unwrap_or_default())
+}
+
+async fn run_query_async(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    query: &QueryBuilder,
+) -> Result<Vec<dto::RunQueryResponse>> {
+    let structured_query = query.build_validated()?;
+
+    let url = firebase_url_method(
+        auth.project_id(),
+        auth.database_id(),
+        query.parent.as_deref(),
+        "runQuery",
+    );
+
+    let query_request = dto::RunQueryRequest {
+        structured_query: Some(structured_query),
         ..Default::default()
     };
 
@@ -152,15 +655,14 @@ pub async fn query_async(
         .send()
         .await?;
 
-    let resp = extract_google_api_error_async(resp, || collection_id.to_owned()).await?;
+    let resp = extract_google_api_error_async(resp, || query.context()).await?;
 
     let json: Option<Vec<dto::RunQueryResponse>> = resp.json().await?;
-
-    Ok(Query(json.unwrap_or_default().into_iter()))
+    Ok(json.unwrap_or_default())
 }
 
-/// This type is returned as a result by [`query()`].
-/// Use it as an iterator. The query API returns a list of document references, not the documents itself.
+/// This type is returned as a result by [`query()`] and [`QueryBuilder::run`].
+/// Use it as an iterator. The [`query()`] API returns a list of document references, not the documents itself.
 ///
 /// If you just need the meta data like the document name or update time, you are already settled.
 /// To fetch the document itself, use [`read_by_name`].
@@ -182,3 +684,154 @@ impl Iterator for Query {
         None
     }
 }
+
+/// This type is returned as a result by [`query_typed()`] and [`QueryBuilder::run_typed`].
+/// Use it as an iterator. Other than [`Query`], the documents are part of the query response
+/// and no additional request per document is necessary.
+///
+/// A tuple of the deserialized document and its metadata (with .name, .create_time, .update_time fields) is returned.
+///
+/// Please note that this API acts as an iterator of same-like documents.
+/// This type is not suitable if you want to list documents of different types.
+pub struct TypedQuery<T>(IntoIter<dto::RunQueryResponse>, std::marker::PhantomData<T>);
+
+impl<T> Iterator for TypedQuery<T>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    type Item = Result<(T, dto::Document)>;
+
+    // Skip empty entries
+    fn next(&mut self) -> Option<Self::Item> {
+        for r in self.0.by_ref() {
+            if let Some(mut document) = r.document {
+                return Some(document_to_pod(&document).map(|pod| {
+                    document.fields = None;
+                    (pod, document)
+                }));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_a_single_filter_without_composite() {
+        let query = QueryBuilder::new("cars")
+            .field_filter("type", dto::FieldOperator::EQUAL, "car")
+            .build();
+        let filter = query.where_.unwrap();
+        assert!(filter.composite_filter.is_none());
+        assert_eq!(filter.field_filter.unwrap().field.field_path, "type");
+    }
+
+    #[test]
+    fn it_combines_filters_with_and() -> Result<()> {
+        let query = QueryBuilder::new("cars")
+            .field_filter("year", dto::FieldOperator::GREATER_THAN, 2015)
+            .filter(dto::Filter::or(vec![
+                dto::Filter::field("color", dto::FieldOperator::EQUAL, "red"),
+                dto::Filter::unary("color", dto::UnaryOperator::IS_NULL),
+            ]))
+            .order_by("year", dto::Direction::DESCENDING)
+            .start_after(vec![2016.into()])
+            .limit(5)
+            .build();
+
+        let json = serde_json::to_value(&query)?;
+        assert_eq!(json["where"]["compositeFilter"]["op"], "AND");
+        assert_eq!(
+            json["where"]["compositeFilter"]["filters"][1]["compositeFilter"]["op"],
+            "OR"
+        );
+        assert_eq!(json["orderBy"][0]["direction"], "DESCENDING");
+        assert_eq!(json["startAt"]["values"][0]["integerValue"], "2016");
+        assert_eq!(json["startAt"]["before"], false);
+        assert_eq!(json["limit"], 5);
+        assert_eq!(json["from"][0]["collectionId"], "cars");
+        assert!(json.get("select").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn it_validates_array_operators() {
+        let values = |n: i64| dto::Value::try_from(serde_json::Value::from((0..n).collect::<Vec<_>>())).unwrap();
+        let valid = dto::Filter::field("id", dto::FieldOperator::IN, values(30));
+        assert!(validate_filter(&valid).is_ok());
+
+        for filter in vec![
+            dto::Filter::field("id", dto::FieldOperator::IN, values(31)),
+            dto::Filter::field("id", dto::FieldOperator::IN, values(0)),
+            dto::Filter::field("id", dto::FieldOperator::NOT_IN, values(11)),
+            dto::Filter::field("id", dto::FieldOperator::ARRAY_CONTAINS_ANY, "no array"),
+            dto::Filter::or(vec![dto::Filter::field("id", dto::FieldOperator::IN, 12)]),
+            dto::Filter::unary("id", dto::UnaryOperator::OPERATOR_UNSPECIFIED),
+            dto::Filter::and(vec![]),
+        ] {
+            match validate_filter(&filter) {
+                Err(FirebaseError::InvalidQuery(_)) => {}
+                _ => panic!("Expected an InvalidQuery error for {:?}", filter),
+            }
+        }
+    }
+
+    #[test]
+    fn it_splits_nested_collections_into_parent_and_collection_id() {
+        let query = QueryBuilder::new("posts/abc/comments");
+        assert_eq!(query.parent.as_deref(), Some("posts/abc"));
+        assert_eq!(query.context(), "posts/abc/comments");
+        let from = query.build().from.unwrap();
+        assert_eq!(from[0].collection_id.as_deref(), Some("comments"));
+        assert!(from[0].all_descendants.is_none());
+
+        let query = QueryBuilder::collection_group("comments").parent("posts/abc");
+        let from = query.build().from.unwrap();
+        assert_eq!(from[0].collection_id.as_deref(), Some("comments"));
+        assert_eq!(from[0].all_descendants, Some(true));
+        assert_eq!(query.parent.as_deref(), Some("posts/abc"));
+    }
+
+    #[test]
+    fn it_selects_fields() -> Result<()> {
+        let query = QueryBuilder::new("cars").select(vec!["color", "year"]).build();
+        let json = serde_json::to_value(&query)?;
+        assert_eq!(json["select"]["fields"][1]["fieldPath"], "year");
+
+        let query = QueryBuilder::new("cars").select(Vec::<String>::new()).build();
+        let json = serde_json::to_value(&query)?;
+        assert_eq!(json["select"]["fields"], serde_json::json!([]));
+        Ok(())
+    }
+
+    #[test]
+    fn it_deserializes_typed_query_results() {
+        #[derive(Deserialize)]
+        struct Car {
+            color: String,
+        }
+
+        let response: Vec<dto::RunQueryResponse> = serde_json::from_str(
+            r#"[
+            {"readTime": "2020-04-28T14:52:51.250511Z"},
+            {"document": {
+                "name": "projects/p/databases/(default)/documents/cars/1",
+                "fields": {"color": {"stringValue": "red"}},
+                "createTime": "2020-04-28T14:52:51.250511Z",
+                "updateTime": "2020-04-28T14:52:51.250511Z"
+            }}
+            ]"#,
+        )
+        .unwrap();
+        let results: Vec<(Car, dto::Document)> = TypedQuery(response.into_iter(), std::marker::PhantomData)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.color, "red");
+        assert_eq!(results[0].1.name, "projects/p/databases/(default)/documents/cars/1");
+        assert!(results[0].1.fields.is_none());
+    }
+}
@@ -13,7 +13,7 @@ pub fn read_by_name<T>(auth: &impl FirebaseAuthBearer, document_name: impl AsRef
 where
     for<'b> T: Deserialize<'b>,
 {
-    let resp = request_document(auth, document_name)?;
+    let resp = request_document(auth, document_name, &[])?;
     // Here `resp.json()?` is a method provided by `reqwest`
     let json: dto::Document = resp.json()?;
     document_to_pod(&json)
@@ -29,7 +29,7 @@ pub async fn read_by_name_async<T>(auth: &mut impl FirebaseAuthBearerAsync, docu
 where
     for<'b> T: Deserialize<'b>,
 {
-    let resp = request_document_async(auth, document_name).await?;
+    let resp = request_document_async(auth, document_name, &[]).await?;
     // Here `resp.json()?` is a method provided by `reqwest`
     let json: dto::Document = resp.json().await?;
     document_to_pod(&json)
@@ -46,10 +46,166 @@ pub fn read<T>(auth: &impl FirebaseAuthBearer, path: &str, document_id: impl AsR
 where
     for<'b> T: Deserialize<'b>,
 {
-    let document_name = document_name(auth.project_id(), path, document_id);
+    let document_name = document_name(auth.project_id(), auth.database_id(), path, document_id);
     read_by_name(auth, document_name)
 }
 
+///
+/// Read only some fields of a document into a type `T` (usually a struct with a subset of the document fields).
+///
+/// Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct DemoPartialDTO { a_string: String }
+///
+/// use firestore_db_and_auth::documents;
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let doc: DemoPartialDTO = documents::read_with_mask(&session, "tests", "service_test", &["a_string"])?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * `auth` The authentication token
+/// * `path` The document path / collection; For example `my_collection` or `a/nested/collection`
+/// * `document_id` The document id. Make sure that you do not include the document id to the path argument.
+/// * `mask` The field paths to return. Fields that are not part of the mask are not transferred.
+pub fn read_with_mask<T>(
+    auth: &impl FirebaseAuthBearer,
+    path: &str,
+    document_id: impl AsRef<str>,
+    mask: &[&str],
+) -> Result<T>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let document_name = document_name(auth.project_id(), auth.database_id(), path, document_id);
+    let resp = request_document(auth, document_name, mask)?;
+    let json: dto::Document = resp.json()?;
+    document_to_pod(&json)
+}
+
+/// A document read by [`read_with_meta()`], together with its metadata
+#[derive(Clone, Debug)]
+pub struct DocumentSnapshot<T> {
+    /// The document id, the last segment of the name
+    pub id: String,
+    /// The absolute document name, like "projects/{PROJECT_ID}/databases/(default)/documents/my_collection/document_id"
+    pub name: String,
+    /// The time the document was created. `None` if the document does not exist.
+    pub create_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// The time the document was last changed. `None` if the document does not exist.
+    pub update_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// The deserialized document. `None` if the document does not exist.
+    pub data: Option<T>,
+}
+
+impl<T> DocumentSnapshot<T> {
+    /// Returns true if the document exists
+    pub fn exists(&self) -> bool {
+        self.data.is_some()
+    }
+
+    /// A snapshot of a document that does not exist
+    fn missing(name: String) -> Result<Self> {
+        Ok(DocumentSnapshot {
+            id: document_id_of_name(&name)?,
+            name,
+            create_time: None,
+            update_time: None,
+            data: None,
+        })
+    }
+
+    /// Converts a batch get entry and parses the metadata of found documents
+    pub(crate) fn from_entry(entry: BatchGetEntry<T>) -> Result<Self> {
+        match entry {
+            BatchGetEntry::Found(data, document) => Ok(DocumentSnapshot {
+                id: document_id_of_name(&document.name)?,
+                create_time: parse_rfc3339(document.create_time, "Failed to parse the created time")?,
+                update_time: parse_rfc3339(document.update_time, "Failed to parse the update time")?,
+                data: Some(data),
+                name: document.name,
+            }),
+            BatchGetEntry::Missing(name) => DocumentSnapshot::missing(name),
+        }
+    }
+}
+
+/// Takes the only entry of a batch get of a single document
+fn single_entry<T>(mut entries: Vec<BatchGetEntry<T>>) -> Result<BatchGetEntry<T>> {
+    match (entries.pop(), entries.is_empty()) {
+        (Some(entry), true) => Ok(entry),
+        _ => Err(FirebaseError::Generic("Expected exactly one batch get entry")),
+    }
+}
+
+///
+/// Read a document of a specific type from a collection, together with its id, name, create and update time.
+///
+/// In contrast to [`read()`], a document that does not exist is not an error.
+/// The returned snapshot does not contain data in that case.
+/// The document is read with a batch get, which reports missing documents explicitly.
+/// Other errors, for example of a database that does not exist, are returned as errors.
+///
+/// Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct DemoDTO { a_string: String, an_int: u32, }
+///
+/// use firestore_db_and_auth::documents;
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let snapshot = documents::read_with_meta::<DemoDTO>(&session, "tests", "service_test")?;
+/// match snapshot.data {
+///     Some(doc) => println!("{:?}, last changed at {:?}", doc, snapshot.update_time),
+///     None => println!("{} does not exist", snapshot.name),
+/// }
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * `auth` The authentication token
+/// * `path` The document path / collection; For example `my_collection` or `a/nested/collection`
+/// * `document_id` The document id. Make sure that you do not include the document id to the path argument.
+pub fn read_with_meta<T>(
+    auth: &impl FirebaseAuthBearer,
+    path: &str,
+    document_id: impl AsRef<str>,
+) -> Result<DocumentSnapshot<T>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let document_path = format!("{}/{}", path, document_id.as_ref());
+    DocumentSnapshot::from_entry(single_entry(batch_get(auth, &[document_path])?)?)
+}
+
+///
+/// Read a document of a specific type from a collection, together with its id, name, create and update time.
+/// See [`read_with_meta()`].
+///
+/// ## Arguments
+/// * `auth` The authentication token
+/// * `path` The document path / collection; For example `my_collection` or `a/nested/collection`
+/// * `document_id` The document id. Make sure that you do not include the document id to the path argument.
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn read_with_meta_async<T>(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    path: &str,
+    document_id: impl AsRef<str>,
+) -> Result<DocumentSnapshot<T>>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let document_path = format!("{}/{}", path, document_id.as_ref());
+    DocumentSnapshot::from_entry(single_entry(batch_get_async(auth, &[document_path]).await?)?)
+}
+
 /// Return the raw unparsed content of the Firestore document. Methods like
 /// [`read()`](../documents/fn.read.html) will deserialize the JSON-encoded
 /// response into a known type `T`
@@ -58,8 +214,8 @@ where
 /// response. This will raise `FirebaseError::IO` if there are errors reading the stream. Please
 /// see [`read_to_end()`](https://doc.rust-lang.org/std/io/trait.Read.html#method.read_to_end)
 pub fn contents(auth: &impl FirebaseAuthBearer, path: &str, document_id: impl AsRef<str>) -> Result<String> {
-    let document_name = document_name(auth.project_id(), path, document_id);
-    let mut resp = request_document(auth, document_name)?;
+    let document_name = document_name(auth.project_id(), auth.database_id(), path, document_id);
+    let mut resp = request_document(auth, document_name, &[])?;
     let mut text = String::new();
     match resp.read_to_string(&mut text) {
         Ok(_bytes) => Ok(text),
@@ -82,10 +238,33 @@ pub async fn read_async<T>(
 where
     for<'b> T: Deserialize<'b>,
 {
-    let document_name = document_name(auth.project_id(), path, document_id);
+    let document_name = document_name(auth.project_id(), auth.database_id(), path, document_id);
     read_by_name_async(auth, &document_name).await
 }
 
+///
+/// Read only some fields of a document into a type `T` (usually a struct with a subset of the document fields).
+///
+/// ## Arguments
+/// * `auth` The authentication token
+/// * `path` The document path / collection; For example `my_collection` or `a/nested/collection`
+/// * `document_id` The document id. Make sure that you do not include the document id to the path argument.
+/// * `mask` The field paths to return. Fields that are not part of the mask are not transferred.
+pub async fn read_with_mask_async<T>(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    path: &str,
+    document_id: impl AsRef<str>,
+    mask: &[&str],
+) -> Result<T>
+where
+    for<'b> T: Deserialize<'b>,
+{
+    let document_name = document_name(auth.project_id(), auth.database_id(), path, document_id);
+    let resp = request_document_async(auth, document_name, mask).await?;
+    let json: dto::Document = resp.json().await?;
+    document_to_pod(&json)
+}
+
 /// Return the raw unparsed content of the Firestore document. Methods like
 /// [`read()`](../documents/fn.read.html) will deserialize the JSON-encoded
 /// response into a known type `T`
@@ -98,8 +277,8 @@ pub async fn contents_async(
     path: &str,
     document_id: impl AsRef<str>,
 ) -> Result<String> {
-    let document_name = document_name(auth.project_id(), path, document_id);
-    let resp = request_document_async(auth, document_name).await?;
+    let document_name = document_name(auth.project_id(), auth.database_id(), path, document_id);
+    let resp = request_document_async(auth, document_name, &[]).await?;
     let mut text = String::new();
     let byte = match resp.bytes().await {
         Ok(_bytes) => _bytes,
@@ -118,12 +297,14 @@ pub async fn contents_async(
 fn request_document(
     auth: &impl FirebaseAuthBearer,
     document_name: impl AsRef<str>,
+    mask: &[&str],
 ) -> Result<reqwest::blocking::Response> {
     let url = firebase_url_base(document_name.as_ref());
 
     let resp = auth
         .client()
         .get(url)
+        .query(&mask_query_params(mask))
         .bearer_auth(auth.access_token().to_owned())
         .send()?;
 
@@ -135,12 +316,14 @@ fn request_document(
 async fn request_document_async(
     auth: &mut impl FirebaseAuthBearerAsync,
     document_name: impl AsRef<str>,
+    mask: &[&str],
 ) -> Result<reqwest::Response> {
     let url = firebase_url_base(document_name.as_ref());
 
     let resp = auth
         .client_async()
         .get(&url)
+        .query(&mask_query_params(mask))
         .bearer_auth(auth.access_token().await.to_string())
         .send()
         .await?;
@@ -149,10 +332,16 @@ async fn request_document_async(
 }
 
 /// Simple method to join the path and document identifier in correct format
-fn document_name(project_id: impl AsRef<str>, path: impl AsRef<str>, document_id: impl AsRef<str>) -> String {
+fn document_name(
+    project_id: impl AsRef<str>,
+    database_id: impl AsRef<str>,
+    path: impl AsRef<str>,
+    document_id: impl AsRef<str>,
+) -> String {
     format!(
-        "projects/{}/databases/(default)/documents/{}/{}",
+        "projects/{}/databases/{}/documents/{}/{}",
         project_id.as_ref(),
+        database_id.as_ref(),
         path.as_ref(),
         document_id.as_ref()
     )
@@ -164,7 +353,7 @@ fn it_document_name_joins_paths() {
     let path = "one/two/three";
     let document_id = "my-document";
     assert_eq!(
-        document_name(&project_id, &path, &document_id),
+        document_name(&project_id, DEFAULT_DATABASE_ID, &path, &document_id),
         "projects/firebase-project/databases/(default)/documents/one/two/three/my-document"
     );
 }
@@ -175,7 +364,63 @@ fn it_document_name_joins_invalid_path_fragments() {
     let path = "one/two//three/";
     let document_id = "///my-document";
     assert_eq!(
-        document_name(&project_id, &path, &document_id),
+        document_name(&project_id, DEFAULT_DATABASE_ID, &path, &document_id),
         "projects/firebase-project/databases/(default)/documents/one/two//three/////my-document"
     );
 }
+
+#[test]
+fn it_creates_document_snapshots() -> Result<()> {
+    #[derive(Deserialize)]
+    struct DemoDTO {
+        a_string: String,
+    }
+    let responses: Vec<dto::BatchGetDocumentsResponse> = serde_json::from_value(serde_json::json!([
+        {"found": {
+            "name": "projects/p/databases/(default)/documents/tests/one",
+            "fields": {"a_string": {"stringValue": "abc"}},
+            "createTime": "2020-04-28T14:52:51.250511Z",
+            "updateTime": "2020-04-29T14:52:51.250511Z"
+        }},
+        {"missing": "projects/p/databases/(default)/documents/tests/two"}
+    ]))?;
+    let names = vec![
+        "projects/p/databases/(default)/documents/tests/one".to_owned(),
+        "projects/p/databases/(default)/documents/tests/two".to_owned(),
+    ];
+    let mut entries = batch_get_entries::<DemoDTO>(names, responses)?.into_iter();
+
+    let snapshot = DocumentSnapshot::from_entry(entries.next().unwrap())?;
+    assert!(snapshot.exists());
+    assert_eq!(snapshot.id, "one");
+    assert!(snapshot.create_time < snapshot.update_time);
+    assert_eq!(snapshot.data.unwrap().a_string, "abc");
+
+    let missing = DocumentSnapshot::from_entry(entries.next().unwrap())?;
+    assert!(!missing.exists());
+    assert_eq!(missing.id, "two");
+    assert_eq!(missing.update_time, None);
+
+    assert!(single_entry::<DemoDTO>(vec![]).is_err());
+    Ok(())
+}
+
+#[test]
+fn it_builds_read_mask_query_params() -> Result<()> {
+    let zip_code = field_path(&["address", "zip code"]);
+    let mask = ["name", zip_code.as_str()];
+    assert_eq!(
+        mask_query_params(&mask),
+        vec![("mask.fieldPaths", "name"), ("mask.fieldPaths", "address.`zip code`")]
+    );
+    let name = "projects/p/databases/(default)/documents/tests/one";
+    let request = reqwest::blocking::Client::new()
+        .get(firebase_url_base(name))
+        .query(&mask_query_params(&mask))
+        .build()?;
+    assert_eq!(
+        request.url().query(),
+        Some("mask.fieldPaths=name&mask.fieldPaths=address.%60zip+code%60")
+    );
+    Ok(())
+}
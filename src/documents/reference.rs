@@ -0,0 +1,314 @@
+use super::*;
+use std::fmt;
+
+/// The maximum size of a field path or document path in bytes
+const MAX_PATH_BYTES: usize = 1500;
+
+///
+/// A validated, relative path to a collection, like "my_collection" or "a/nested/collection".
+///
+/// Collection paths have an odd number of segments. Use [`CollectionRef::doc`] to refer to a
+/// document of the collection.
+///
+/// Example:
+/// ```
+/// use firestore_db_and_auth::documents::CollectionRef;
+///
+/// let users = CollectionRef::new("users")?;
+/// let posts = users.doc("alice")?.collection("posts")?;
+/// assert_eq!(posts.path(), "users/alice/posts");
+/// assert_eq!(posts.parent().unwrap().id(), "alice");
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CollectionRef {
+    path: String,
+}
+
+///
+/// A validated, relative path to a document, like "my_collection/document_id".
+///
+/// Document paths have an even number of segments. The relative path can be used with all
+/// functions of this module, for example:
+/// ```no_run
+/// # use serde::Deserialize;
+/// # #[derive(Deserialize)]
+/// # struct DemoDTO { a_string: String }
+/// use firestore_db_and_auth::documents::{self, DocumentRef};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// for metadata in documents::query(&session, "tests", "Sam Weiss".into(), dto::FieldOperator::EQUAL, "id")? {
+///     let document = DocumentRef::from_name(&metadata.name)?;
+///     let doc: DemoDTO = documents::read(&session, document.parent().path(), document.id())?;
+///     documents::delete(&session, document.path(), true)?;
+/// }
+/// # use firestore_db_and_auth::dto;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DocumentRef {
+    path: String,
+}
+
+impl CollectionRef {
+    /// Creates a collection reference from a relative path like "my_collection" or "a/nested/collection".
+    /// Returns [`FirebaseError::InvalidPath`] for document paths and invalid segments.
+    pub fn new(path: &str) -> Result<Self> {
+        let segments = validate_path(path)?;
+        if segments % 2 == 0 {
+            return Err(FirebaseError::InvalidPath(format!(
+                "{} is a document path, not a collection path",
+                path
+            )));
+        }
+        Ok(CollectionRef { path: path.to_owned() })
+    }
+
+    /// Creates a collection reference from an absolute name like
+    /// "projects/{PROJECT_ID}/databases/(default)/documents/my_collection" of any database.
+    pub fn from_name(name: &str) -> Result<Self> {
+        CollectionRef::new(relative_path(name)?)
+    }
+
+    /// The relative path, for example "a/nested/collection"
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The collection id, the last segment of the path. For example "collection" of "a/nested/collection".
+    pub fn id(&self) -> &str {
+        last_segment(&self.path)
+    }
+
+    /// The document that contains this collection. `None` for root collections.
+    pub fn parent(&self) -> Option<DocumentRef> {
+        self.path.rsplit_once('/').map(|(parent, _)| DocumentRef {
+            path: parent.to_owned(),
+        })
+    }
+
+    /// A reference to the document with the given id in this collection
+    /// Returns [`FirebaseError::InvalidPath`] if the id is not a single valid segment.
+    pub fn doc(&self, document_id: &str) -> Result<DocumentRef> {
+        validate_id(document_id)?;
+        DocumentRef::new(&format!("{}/{}", self.path, document_id))
+    }
+
+    /// The absolute name in the default database, like "projects/{PROJECT_ID}/databases/(default)/documents/my_collection"
+    pub fn name(&self, project_id: &str) -> String {
+        firebase_document_name(project_id, DEFAULT_DATABASE_ID, &self.path)
+    }
+
+    /// The absolute name in the given database, like "projects/{PROJECT_ID}/databases/{DATABASE_ID}/documents/my_collection"
+    pub fn name_in_database(&self, project_id: &str, database_id: &str) -> String {
+        firebase_document_name(project_id, database_id, &self.path)
+    }
+}
+
+impl DocumentRef {
+    /// Creates a document reference from a relative path like "my_collection/document_id".
+    /// Returns [`FirebaseError::InvalidPath`] for collection paths and invalid segments.
+    pub fn new(path: &str) -> Result<Self> {
+        let segments = validate_path(path)?;
+        if segments % 2 != 0 {
+            return Err(FirebaseError::InvalidPath(format!(
+                "{} is a collection path, not a document path",
+                path
+            )));
+        }
+        Ok(DocumentRef { path: path.to_owned() })
+    }
+
+    /// Creates a document reference from an absolute name like
+    /// "projects/{PROJECT_ID}/databases/(default)/documents/my_collection/document_id" of any database,
+    /// as returned by [`list()`] and [`query()`].
+    pub fn from_name(name: &str) -> Result<Self> {
+        DocumentRef::new(relative_path(name)?)
+    }
+
+    /// The relative path, for example "my_collection/document_id"
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The document id, the last segment of the path
+    pub fn id(&self) -> &str {
+        last_segment(&self.path)
+    }
+
+    /// The collection that contains this document
+    pub fn parent(&self) -> CollectionRef {
+        let (parent, _) = self.path.rsplit_once('/').unwrap_or_default();
+        CollectionRef {
+            path: parent.to_owned(),
+        }
+    }
+
+    /// A reference to the sub-collection with the given id of this document
+    /// Returns [`FirebaseError::InvalidPath`] if the id is not a single valid segment.
+    pub fn collection(&self, collection_id: &str) -> Result<CollectionRef> {
+        validate_id(collection_id)?;
+        CollectionRef::new(&format!("{}/{}", self.path, collection_id))
+    }
+
+    /// The absolute name in the default database, like "projects/{PROJECT_ID}/databases/(default)/documents/my_collection/document_id"
+    pub fn name(&self, project_id: &str) -> String {
+        firebase_document_name(project_id, DEFAULT_DATABASE_ID, &self.path)
+    }
+
+    /// The absolute name in the given database, like "projects/{PROJECT_ID}/databases/{DATABASE_ID}/documents/my_collection/document_id"
+    pub fn name_in_database(&self, project_id: &str, database_id: &str) -> String {
+        firebase_document_name(project_id, database_id, &self.path)
+    }
+}
+
+impl fmt::Display for CollectionRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl fmt::Display for DocumentRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Returns the part after ".../documents/" of an absolute name
+fn relative_path(name: &str) -> Result<&str> {
+    let mut segments = name.splitn(6, '/');
+    match (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) {
+        (Some("projects"), Some(project), Some("databases"), Some(database), Some("documents"), Some(path))
+            if !project.is_empty() && !database.is_empty() =>
+        {
+            Ok(path)
+        }
+        _ => Err(FirebaseError::InvalidPath(format!(
+            "{} is not an absolute name like projects/{{PROJECT_ID}}/databases/(default)/documents/...",
+            name
+        ))),
+    }
+}
+
+/// Checks the path segments against the Firestore naming rules and returns the number of segments
+fn validate_path(path: &str) -> Result<usize> {
+    if path.len() > MAX_PATH_BYTES {
+        return Err(FirebaseError::InvalidPath(format!(
+            "The path is longer than {} bytes",
+            MAX_PATH_BYTES
+        )));
+    }
+    let mut count = 0;
+    for segment in path.split('/') {
+        if let Some(reason) = invalid_segment(segment) {
+            return Err(FirebaseError::InvalidPath(format!("{} in path '{}'", reason, path)));
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Checks a single document or collection id, which must not contain '/'
+fn validate_id(id: &str) -> Result<()> {
+    let reason = if id.contains('/') {
+        Some("Ids must not contain '/'")
+    } else {
+        invalid_segment(id)
+    };
+    match reason {
+        Some(reason) => Err(FirebaseError::InvalidPath(format!("{} in id '{}'", reason, id))),
+        None => Ok(()),
+    }
+}
+
+/// Returns the reason why a path segment violates the Firestore naming rules
+fn invalid_segment(segment: &str) -> Option<&'static str> {
+    match segment {
+        "" => Some("Empty segment"),
+        "." | ".." => Some("Segments must not be '.' or '..'"),
+        s if s.len() >= 4 && s.starts_with("__") && s.ends_with("__") => {
+            Some("Segments must not start and end with '__'")
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_navigates_paths() -> Result<()> {
+        let document = DocumentRef::new("users/alice/posts/first")?;
+        assert_eq!(document.id(), "first");
+        assert_eq!(document.parent(), CollectionRef::new("users/alice/posts")?);
+        assert_eq!(document.parent().parent(), Some(DocumentRef::new("users/alice")?));
+        assert_eq!(CollectionRef::new("users")?.parent(), None);
+        assert_eq!(
+            document.collection("comments")?.path(),
+            "users/alice/posts/first/comments"
+        );
+        assert_eq!(
+            document.name("p"),
+            "projects/p/databases/(default)/documents/users/alice/posts/first"
+        );
+        assert_eq!(
+            document.name_in_database("p", "eu-tenant"),
+            "projects/p/databases/eu-tenant/documents/users/alice/posts/first"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn it_converts_names() -> Result<()> {
+        let name = "projects/p/databases/(default)/documents/users/alice";
+        assert_eq!(DocumentRef::from_name(name)?.path(), "users/alice");
+        assert_eq!(CollectionRef::from_name(&format!("{}/posts", name))?.id(), "posts");
+        assert!(DocumentRef::from_name("users/alice").is_err());
+        assert!(DocumentRef::from_name(&format!("{}/posts", name)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_invalid_paths() {
+        for path in &[
+            "",
+            "users/",
+            "/users",
+            "users//alice",
+            "users/..",
+            "users/__id__",
+            "users/____",
+        ] {
+            assert!(
+                matches!(CollectionRef::new(path), Err(FirebaseError::InvalidPath(_))),
+                "{}",
+                path
+            );
+        }
+        assert!(CollectionRef::new("users/alice").is_err());
+        assert!(DocumentRef::new("users").is_err());
+        assert!(CollectionRef::new("users").unwrap().doc("a/b").is_err());
+        let users = CollectionRef::new("users").unwrap();
+        for id in &["alice/posts/x", "", "..", "____"] {
+            assert!(matches!(users.doc(id), Err(FirebaseError::InvalidPath(_))), "{}", id);
+        }
+        let alice = users.doc("alice").unwrap();
+        assert!(matches!(
+            alice.collection("posts/x/comments"),
+            Err(FirebaseError::InvalidPath(_))
+        ));
+    }
+}
@@ -0,0 +1,441 @@
+use super::*;
+use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
+use futures_util::future::BoxFuture;
+use std::time::Duration;
+
+/// The wait time before the first retry of an aborted transaction. It doubles with every further retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Transaction options. The default is a read-write transaction that is attempted up to 5 times.
+pub struct TransactionOptions {
+    /// A read-only transaction cannot stage writes, but does not lock the documents it reads.
+    pub read_only: bool,
+    /// How often the transaction is attempted, if Firestore aborts it because of concurrent modifications.
+    pub max_attempts: u32,
+}
+
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        TransactionOptions {
+            read_only: false,
+            max_attempts: 5,
+        }
+    }
+}
+
+///
+/// A read-write transaction. Read documents with [`Transaction::get`] and stage writes with
+/// [`Transaction::set`], [`Transaction::update`] and [`Transaction::delete`].
+/// All reads must happen before the first write is staged.
+///
+/// Staged writes are committed atomically by [`run_transaction()`], after the closure returned.
+/// The commit fails if any document that was read has been modified in the meantime.
+pub struct Transaction<'a, A> {
+    auth: &'a A,
+    id: String,
+    writes: WriteBatch,
+}
+
+impl<'a, A: FirebaseAuthBearer> Transaction<'a, A> {
+    /// The transaction id
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Reads a document as part of the transaction. Returns `None` if the document does not exist.
+    ///
+    /// ## Arguments
+    /// * 'path' The relative collection path and document id, for example "my_collection/document_id"
+    pub fn get<T>(&self, path: &str) -> Result<Option<T>>
+    where
+        for<'b> T: Deserialize<'b>,
+    {
+        let entries = batch_get_in_transaction(self.auth, &[path], Some(&self.id))?;
+        Ok(entries.into_iter().next().and_then(BatchGetEntry::found))
+    }
+
+    /// Reads multiple documents as part of the transaction, see [`batch_get()`].
+    ///
+    /// ## Arguments
+    /// * 'paths' The relative collection paths and document ids, for example "my_collection/document_id"
+    pub fn get_all<T>(&self, paths: &[impl AsRef<str>]) -> Result<Vec<BatchGetEntry<T>>>
+    where
+        for<'b> T: Deserialize<'b>,
+    {
+        batch_get_in_transaction(self.auth, paths, Some(&self.id))
+    }
+
+    /// Stages a write of the given document, see [`WriteBatch::set`].
+    pub fn set<T>(
+        &mut self,
+        path: &str,
+        document_id: impl AsRef<str>,
+        document: &T,
+        options: WriteOptions,
+    ) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        self.writes.set(path, document_id, document, options)?;
+        Ok(self)
+    }
+
+    /// Stages a merge of the fields of the given document into an existing document, see [`WriteBatch::update`].
+    pub fn update<T>(&mut self, path: &str, document_id: impl AsRef<str>, document: &T) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        self.writes.update(path, document_id, document)?;
+        Ok(self)
+    }
+
+    /// Stages the deletion of a document, see [`WriteBatch::delete`].
+    pub fn delete(&mut self, path: &str) -> &mut Self {
+        self.writes.delete(path);
+        self
+    }
+
+    /// Stages the deletion of a document with a precondition, see [`WriteBatch::delete_with_precondition`].
+    pub fn delete_with_precondition(&mut self, path: &str, precondition: Precondition) -> &mut Self {
+        self.writes.delete_with_precondition(path, precondition);
+        self
+    }
+}
+
+///
+/// Runs the given closure in a transaction and commits the staged writes.
+///
+/// If Firestore aborts the transaction because of a concurrent modification, the transaction
+/// is retried with an increasing delay, up to [`TransactionOptions::max_attempts`] times.
+/// The closure is therefore called multiple times and should not have side effects.
+/// If the closure returns an error, the transaction is rolled back and the error is returned.
+///
+/// Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Counter { count: i64 }
+///
+/// use firestore_db_and_auth::documents::{self, TransactionOptions};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let count = documents::run_transaction(&session, TransactionOptions::default(), |tx| {
+///     let counter: Counter = tx.get("counters/visits")?.unwrap_or(Counter { count: 0 });
+///     let counter = Counter { count: counter.count + 1 };
+///     tx.set("counters", "visits", &counter, documents::WriteOptions::default())?;
+///     Ok(counter.count)
+/// })?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'options' Transaction options
+/// * 'f' The closure that reads documents and stages writes
+pub fn run_transaction<A, T, F>(auth: &A, options: TransactionOptions, mut f: F) -> Result<T>
+where
+    A: FirebaseAuthBearer,
+    F: FnMut(&mut Transaction<A>) -> Result<T>,
+{
+    let mut retry_transaction = None;
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        let request = begin_transaction_request(&options, retry_transaction.take());
+        let id = begin_transaction(auth, &request)?;
+        let mut transaction = Transaction {
+            auth,
+            id: id.clone(),
+            writes: WriteBatch::new(),
+        };
+
+        let result = match f(&mut transaction) {
+            Ok(value) => {
+                let writes = transaction.writes.into_writes(auth.project_id(), auth.database_id());
+                commit_writes(auth, writes, Some(id.clone())).map(|_| value)
+            }
+            Err(e) => {
+                // The error of the closure is more relevant than a failed rollback
+                let _ = rollback(auth, &id);
+                Err(e)
+            }
+        };
+
+        match result {
+            Err(FirebaseError::Aborted(..)) if attempt < options.max_attempts => {
+                std::thread::sleep(delay);
+                retry_transaction = Some(id);
+            }
+            result => return result,
+        }
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+///
+/// A read-write transaction of an async session. See [`Transaction`].
+pub struct AsyncTransaction<'a, A> {
+    auth: &'a mut A,
+    id: String,
+    writes: WriteBatch,
+}
+
+impl<'a, A: FirebaseAuthBearerAsync> AsyncTransaction<'a, A> {
+    /// The transaction id
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Reads a document as part of the transaction. Returns `None` if the document does not exist.
+    ///
+    /// ## Arguments
+    /// * 'path' The relative collection path and document id, for example "my_collection/document_id"
+    ///
+    /// THIS IS A NON-BLOCKING OPERATION
+    pub async fn get<T>(&mut self, path: &str) -> Result<Option<T>>
+    where
+        for<'b> T: Deserialize<'b>,
+    {
+        let entries = batch_get_in_transaction_async(&mut *self.auth, &[path], Some(&self.id)).await?;
+        Ok(entries.into_iter().next().and_then(BatchGetEntry::found))
+    }
+
+    /// Reads multiple documents as part of the transaction, see [`batch_get_async()`].
+    ///
+    /// ## Arguments
+    /// * 'paths' The relative collection paths and document ids, for example "my_collection/document_id"
+    ///
+    /// THIS IS A NON-BLOCKING OPERATION
+    pub async fn get_all<T>(&mut self, paths: &[impl AsRef<str>]) -> Result<Vec<BatchGetEntry<T>>>
+    where
+        for<'b> T: Deserialize<'b>,
+    {
+        batch_get_in_transaction_async(&mut *self.auth, paths, Some(&self.id)).await
+    }
+
+    /// Stages a write of the given document, see [`WriteBatch::set`].
+    pub fn set<T>(
+        &mut self,
+        path: &str,
+        document_id: impl AsRef<str>,
+        document: &T,
+        options: WriteOptions,
+    ) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        self.writes.set(path, document_id, document, options)?;
+        Ok(self)
+    }
+
+    /// Stages a merge of the fields of the given document into an existing document, see [`WriteBatch::update`].
+    pub fn update<T>(&mut self, path: &str, document_id: impl AsRef<str>, document: &T) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        self.writes.update(path, document_id, document)?;
+        Ok(self)
+    }
+
+    /// Stages the deletion of a document, see [`WriteBatch::delete`].
+    pub fn delete(&mut self, path: &str) -> &mut Self {
+        self.writes.delete(path);
+        self
+    }
+
+    /// Stages the deletion of a document with a precondition, see [`WriteBatch::delete_with_precondition`].
+    pub fn delete_with_precondition(&mut self, path: &str, precondition: Precondition) -> &mut Self {
+        self.writes.delete_with_precondition(path, precondition);
+        self
+    }
+}
+
+///
+/// Runs the given closure in a transaction and commits the staged writes.
+/// See [`run_transaction()`] for the retry behaviour.
+///
+/// The closure returns a boxed future. Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Counter { count: i64 }
+///
+/// use firestore_db_and_auth::documents::{self, TransactionOptions};
+/// # use firestore_db_and_auth::{AsyncServiceSession, credentials::doctest_credentials};
+/// # async fn run() -> firestore_db_and_auth::errors::Result<()> {
+/// # let mut session = AsyncServiceSession::new(doctest_credentials())?;
+///
+/// let count = documents::run_transaction_async(&mut session, TransactionOptions::default(), |tx| {
+///     Box::pin(async move {
+///         let counter: Counter = tx.get("counters/visits").await?.unwrap_or(Counter { count: 0 });
+///         let counter = Counter { count: counter.count + 1 };
+///         tx.set("counters", "visits", &counter, documents::WriteOptions::default())?;
+///         Ok(counter.count)
+///     })
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ## Arguments
+/// * 'auth' The authentication token
+/// * 'options' Transaction options
+/// * 'f' The closure that reads documents and stages writes
+///
+/// THIS IS A NON-BLOCKING OPERATION
+pub async fn run_transaction_async<A, T, F>(auth: &mut A, options: TransactionOptions, mut f: F) -> Result<T>
+where
+    A: FirebaseAuthBearerAsync + Send,
+    F: for<'t, 'b> FnMut(&'t mut AsyncTransaction<'b, A>) -> BoxFuture<'t, Result<T>>,
+{
+    let mut retry_transaction = None;
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        let request = begin_transaction_request(&options, retry_transaction.take());
+        let id = begin_transaction_async(auth, &request).await?;
+        let mut transaction = AsyncTransaction {
+            auth: &mut *auth,
+            id: id.clone(),
+            writes: WriteBatch::new(),
+        };
+
+        let result = f(&mut transaction).await;
+        let writes = transaction.writes;
+        let result = match result {
+            Ok(value) => {
+                let writes = writes.into_writes(auth.project_id(), auth.database_id());
+                commit_writes_async(auth, writes, Some(id.clone())).await.map(|_| value)
+            }
+            Err(e) => {
+                // The error of the closure is more relevant than a failed rollback
+                let _ = rollback_async(auth, &id).await;
+                Err(e)
+            }
+        };
+
+        match result {
+            Err(FirebaseError::Aborted(..)) if attempt < options.max_attempts => {
+                tokio::time::sleep(delay).await;
+                retry_transaction = Some(id);
+            }
+            result => return result,
+        }
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+fn begin_transaction_request(
+    options: &TransactionOptions,
+    retry_transaction: Option<String>,
+) -> dto::BeginTransactionRequest {
+    let options = if options.read_only {
+        dto::TransactionOptions {
+            read_only: Some(dto::ReadOnly::default()),
+            ..Default::default()
+        }
+    } else {
+        dto::TransactionOptions {
+            read_write: Some(dto::ReadWrite { retry_transaction }),
+            ..Default::default()
+        }
+    };
+    dto::BeginTransactionRequest { options: Some(options) }
+}
+
+fn begin_transaction(auth: &impl FirebaseAuthBearer, request: &dto::BeginTransactionRequest) -> Result<String> {
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "beginTransaction");
+
+    let resp = auth
+        .client()
+        .post(url)
+        .bearer_auth(auth.access_token().to_owned())
+        .json(request)
+        .send()?;
+
+    let resp = extract_google_api_error(resp, || "beginTransaction".to_owned())?;
+
+    let json: dto::BeginTransactionResponse = resp.json()?;
+    json.transaction
+        .ok_or(FirebaseError::Generic("No transaction id in beginTransaction response"))
+}
+
+async fn begin_transaction_async(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    request: &dto::BeginTransactionRequest,
+) -> Result<String> {
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "beginTransaction");
+
+    let resp = auth
+        .client_async()
+        .post(&url)
+        .bearer_auth(auth.access_token().await.to_string())
+        .json(request)
+        .send()
+        .await?;
+
+    let resp = extract_google_api_error_async(resp, || "beginTransaction".to_owned()).await?;
+
+    let json: dto::BeginTransactionResponse = resp.json().await?;
+    json.transaction
+        .ok_or(FirebaseError::Generic("No transaction id in beginTransaction response"))
+}
+
+fn rollback(auth: &impl FirebaseAuthBearer, transaction: &str) -> Result<()> {
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "rollback");
+    let request = dto::RollbackRequest {
+        transaction: Some(transaction.to_owned()),
+    };
+
+    let resp = auth
+        .client()
+        .post(url)
+        .bearer_auth(auth.access_token().to_owned())
+        .json(&request)
+        .send()?;
+
+    extract_google_api_error(resp, || transaction.to_owned())?;
+    Ok(())
+}
+
+async fn rollback_async(auth: &mut impl FirebaseAuthBearerAsync, transaction: &str) -> Result<()> {
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "rollback");
+    let request = dto::RollbackRequest {
+        transaction: Some(transaction.to_owned()),
+    };
+
+    let resp = auth
+        .client_async()
+        .post(&url)
+        .bearer_auth(auth.access_token().await.to_string())
+        .json(&request)
+        .send()
+        .await?;
+
+    extract_google_api_error_async(resp, || transaction.to_owned()).await?;
+    Ok(())
+}
+
+#[test]
+fn it_serializes_begin_transaction_requests() -> Result<()> {
+    let request = begin_transaction_request(&TransactionOptions::default(), Some("abc".to_owned()));
+    assert_eq!(
+        serde_json::to_value(&request)?,
+        serde_json::json!({"options": {"readWrite": {"retryTransaction": "abc"}}})
+    );
+
+    let options = TransactionOptions {
+        read_only: true,
+        ..Default::default()
+    };
+    let request = begin_transaction_request(&options, None);
+    assert_eq!(
+        serde_json::to_value(&request)?,
+        serde_json::json!({"options": {"readOnly": {}}})
+    );
+    Ok(())
+}
@@ -1,4 +1,8 @@
-use crate::{errors::extract_google_api_error_async, FirebaseAuthBearerAsync};
+use crate::errors::{
+    extract_google_api_error_async, extract_google_api_error_precondition, extract_google_api_error_precondition_async,
+};
+use crate::FirebaseAuthBearerAsync;
+use std::collections::HashMap;
 
 use super::*;
 
@@ -17,9 +21,158 @@ pub struct WriteResult {
 #[derive(Default)]
 pub struct WriteOptions {
     /// If this is set instead of overwriting all fields of a target document, only the given fields will be merged.
-    /// This only works if your document type has Option fields.
-    /// The write will fail, if no document_id is given or the target document does not exist yet.
+    /// `None` fields are merged as null, unless [`WriteOptions::skip_nulls`] is set.
+    /// The write will fail, if no document_id is given or with [`FirebaseError::PreconditionFailed`],
+    /// if the target document does not exist yet.
+    ///
+    /// Nested maps are merged as well: Writing `{"profile": {"address": {"city": "Berlin"}}}` only
+    /// updates the field path `profile.address.city` and keeps all other fields of `profile`.
+    /// A merge without any field, field path or transform fails instead of overwriting the document.
     pub merge: bool,
+    /// The field paths to write, instead of the fields of the given document. Requires [`WriteOptions::merge`],
+    /// writes with an update mask but without merge fail.
+    /// Fields that are part of the mask, but not of the document, are deleted.
+    /// Use [`field_path()`] to build field paths of field names with special characters.
+    pub update_mask: Option<Vec<String>>,
+    /// Server-side transformations, applied in order after the document fields have been written.
+    /// See [`dto::FieldTransform::request_time`] and the other constructors.
+    /// Transformed fields should not be part of the written document.
+    pub transforms: Vec<dto::FieldTransform>,
+    /// A condition on the target document. The write fails with [`FirebaseError::PreconditionFailed`]
+    /// if it is not met. Requires a document_id.
+    /// Merge writes require an existing document, if no precondition is given.
+    pub precondition: Option<Precondition>,
+    /// Do not write `None` and null fields, also of nested maps. By default they are written as `nullValue`.
+    /// With [`WriteOptions::merge`], skipped fields keep their current value,
+    /// and a merge write with only null fields fails.
+    pub skip_nulls: bool,
+}
+
+/// A condition on the target document of a write or delete.
+/// Use it for optimistic concurrency control, without a transaction.
+#[derive(Clone, Debug)]
+pub enum Precondition {
+    /// The document must exist
+    Exists,
+    /// The document must not exist. A write with this precondition only creates a document.
+    NotExists,
+    /// The document must exist and must have been last updated at the given time.
+    /// Use the update time of a [`WriteResult`] or of a read document.
+    UpdateTime(chrono::DateTime<chrono::Utc>),
+}
+
+impl Precondition {
+    fn update_time(time: &chrono::DateTime<chrono::Utc>) -> String {
+        time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+    }
+
+    /// The url query parameter of this precondition, for the patch and delete endpoints
+    pub(crate) fn query_param(&self) -> (&'static str, String) {
+        match self {
+            Precondition::Exists => ("currentDocument.exists", "true".to_owned()),
+            Precondition::NotExists => ("currentDocument.exists", "false".to_owned()),
+            Precondition::UpdateTime(time) => ("currentDocument.updateTime", Self::update_time(time)),
+        }
+    }
+}
+
+impl From<&Precondition> for dto::Precondition {
+    fn from(precondition: &Precondition) -> Self {
+        match precondition {
+            Precondition::Exists => dto::Precondition {
+                exists: Some(true),
+                ..Default::default()
+            },
+            Precondition::NotExists => dto::Precondition {
+                exists: Some(false),
+                ..Default::default()
+            },
+            Precondition::UpdateTime(time) => dto::Precondition {
+                update_time: Some(Precondition::update_time(time)),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl dto::FieldTransform {
+    fn new(field: &str) -> Self {
+        dto::FieldTransform {
+            field_path: Some(field.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the field to the time at which the server processed the request
+    ///
+    /// ## Arguments
+    /// * 'field' The field path. For example "updated_at".
+    pub fn request_time(field: &str) -> Self {
+        dto::FieldTransform {
+            set_to_server_value: Some(dto::ServerValue::REQUEST_TIME),
+            ..Self::new(field)
+        }
+    }
+
+    /// Adds the given value to the field. A missing or non-numeric field is set to the given value.
+    ///
+    /// ## Arguments
+    /// * 'field' The field path. For example "visits".
+    /// * 'value' An integer or double value
+    pub fn increment(field: &str, value: impl Into<dto::Value>) -> Self {
+        dto::FieldTransform {
+            increment: Some(value.into()),
+            ..Self::new(field)
+        }
+    }
+
+    /// Sets the field to the maximum of its current value and the given value.
+    ///
+    /// ## Arguments
+    /// * 'field' The field path. For example "high_score".
+    /// * 'value' An integer or double value
+    pub fn maximum(field: &str, value: impl Into<dto::Value>) -> Self {
+        dto::FieldTransform {
+            maximum: Some(value.into()),
+            ..Self::new(field)
+        }
+    }
+
+    /// Sets the field to the minimum of its current value and the given value.
+    ///
+    /// ## Arguments
+    /// * 'field' The field path. For example "lowest_price".
+    /// * 'value' An integer or double value
+    pub fn minimum(field: &str, value: impl Into<dto::Value>) -> Self {
+        dto::FieldTransform {
+            minimum: Some(value.into()),
+            ..Self::new(field)
+        }
+    }
+
+    /// Appends the given elements to the array field, if they are not yet contained ("array union").
+    ///
+    /// ## Arguments
+    /// * 'field' The field path. For example "tags".
+    /// * 'values' The elements to append
+    pub fn append_missing_elements(field: &str, values: Vec<dto::Value>) -> Self {
+        dto::FieldTransform {
+            append_missing_elements: Some(dto::ArrayValue { values: Some(values) }),
+            ..Self::new(field)
+        }
+    }
+
+    /// Removes all occurrences of the given elements from the array field ("array remove").
+    ///
+    /// ## Arguments
+    /// * 'field' The field path. For example "tags".
+    /// * 'values' The elements to remove
+    pub fn remove_all_from_array(field: &str, values: Vec<dto::Value>) -> Self {
+        dto::FieldTransform {
+            remove_all_from_array: Some(dto::ArrayValue { values: Some(values) }),
+            ..Self::new(field)
+        }
+    }
 }
 
 ///
@@ -30,9 +183,15 @@ pub struct WriteOptions {
 /// If a document_id is given, the document will be created if it does not yet exist.
 /// Except if the "merge" option (see [`WriteOptions::merge`]) is set.
 ///
+/// Use [`WriteOptions::precondition`] to only write if the document has not been modified since it was read,
+/// or to only create a new document.
+///
+/// Writes with [`WriteOptions::transforms`] are sent to the commit endpoint. The document id
+/// is generated on the client if none is given, and the returned `create_time` is always `None`.
+///
 /// Example:
 ///```no_run
-///use firestore_db_and_auth::{Credentials, ServiceSession, documents, errors::Result, FirebaseAuthBearer};
+///use firestore_db_and_auth::{Credentials, BlockingServiceSession, documents, dto, errors::Result, FirebaseAuthBearer};
 ///use serde::{Serialize,Deserialize};
 ///# use firestore_db_and_auth::credentials::doctest_credentials;
 ///
@@ -59,15 +218,27 @@ pub struct WriteOptions {
 /// /// Either via Option<> or by not having the fields in the structure, see DemoPartialDTO.
 /// fn write_partial(session: &impl FirebaseAuthBearer) -> Result<()> {
 ///    let obj = DemoPartialDTO { a_string: None, an_int: 16 };
-///    let result = documents::write(session, "tests", Some("service_test"), &obj, documents::WriteOptions{merge:true})?;
+///    let result = documents::write(session, "tests", Some("service_test"), &obj, documents::WriteOptions{merge:true, ..Default::default()})?;
 ///    println!("id: {}, created: {}, updated: {}", result.document_id, result.create_time.unwrap(), result.update_time.unwrap());
 ///    Ok(())
 /// }
+/// /// Let the server set the modification time and increment a counter.
+/// fn write_transformed(session: &impl FirebaseAuthBearer) -> Result<()> {
+///    let obj = DemoPartialDTO { a_string: None, an_int: 16 };
+///    let transforms = vec![
+///        dto::FieldTransform::request_time("updated_at"),
+///        dto::FieldTransform::increment("another_int", 1),
+///    ];
+///    let options = documents::WriteOptions{merge:true, transforms, ..Default::default()};
+///    documents::write(session, "tests", Some("service_test"), &obj, options)?;
+///    Ok(())
+/// }
 ///
 /// # fn main() -> Result<()> {
-/// #   let session = ServiceSession::new(doctest_credentials())?;
+/// #   let session = BlockingServiceSession::new(doctest_credentials())?;
 /// #   write(&session)?;
 /// #   write_partial(&session)?;
+/// #   write_transformed(&session)?;
 /// #
 /// #   Ok(())
 /// # }
@@ -90,20 +261,39 @@ pub fn write<T>(
 where
     T: Serialize,
 {
-    let mut url = match document_id.as_ref() {
-        Some(document_id) => firebase_url_extended(auth.project_id(), path, document_id.as_ref()),
-        None => firebase_url(auth.project_id(), path),
+    if options.precondition.is_some() && document_id.is_none() {
+        return Err(FirebaseError::Generic(
+            "A write with a precondition requires a document id",
+        ));
+    }
+
+    if !options.transforms.is_empty() {
+        let document_id = document_id.map_or_else(auto_id, |id| Ok(id.as_ref().to_owned()))?;
+        let mut batch = WriteBatch::new();
+        batch.set(path, document_id, document, options)?;
+        return batch
+            .commit(auth)?
+            .pop()
+            .ok_or(FirebaseError::Generic("No write result in commit response"));
+    }
+
+    let url = match document_id.as_ref() {
+        Some(document_id) => firebase_url_extended(auth.project_id(), auth.database_id(), path, document_id.as_ref()),
+        None => firebase_url(auth.project_id(), auth.database_id(), path),
     };
 
-    let firebase_document = pod_to_document(&document)?;
+    let firebase_document = document_for_write(document, &options)?;
 
-    if options.merge && firebase_document.fields.is_some() {
-        let fields = firebase_document.fields.as_ref().unwrap().keys().join(",");
-        url = format!("{}?currentDocument.exists=true&updateMask.fieldPaths={}", url, fields);
+    let mut params: Vec<_> = options.precondition.iter().map(Precondition::query_param).collect();
+    if let Some(mask) = merge_mask(&firebase_document, &options)? {
+        params.extend(mask.into_iter().map(|field_path| ("updateMask.fieldPaths", field_path)));
+        if options.precondition.is_none() {
+            params.push(Precondition::Exists.query_param());
+        }
     }
 
     let builder = if document_id.is_some() {
-        auth.client().patch(&url)
+        auth.client().patch(&url).query(&params)
     } else {
         auth.client().post(&url)
     };
@@ -113,34 +303,25 @@ where
         .json(&firebase_document)
         .send()?;
 
-    let resp = extract_google_api_error(resp, || {
-        document_id.as_ref().map(|f| f.as_ref().to_owned()).unwrap_or_default()
-    })?;
+    let context = || document_id.as_ref().map(|f| f.as_ref().to_owned()).unwrap_or_default();
+    // Merge writes have an implicit exists precondition
+    let resp = if options.precondition.is_some() || options.merge {
+        extract_google_api_error_precondition(resp, context)?
+    } else {
+        extract_google_api_error(resp, context)?
+    };
 
     let result_document: dto::Document = resp.json()?;
-    let document_id = Path::new(&result_document.name)
-        .file_name()
-        .ok_or_else(|| FirebaseError::Generic("Resulting documents 'name' field is not a valid path"))?
-        .to_str()
-        .ok_or_else(|| FirebaseError::Generic("No valid unicode in 'name' field"))?
-        .to_owned();
+    let document_id = document_id_of_name(&result_document.name)?;
 
-    let create_time = match result_document.create_time {
-        Some(f) => Some(
-            chrono::DateTime::parse_from_rfc3339(&f)
-                .map_err(|_| FirebaseError::Generic("Failed to parse rfc3339 date from 'create_time' field"))?
-                .with_timezone(&chrono::Utc),
-        ),
-        None => None,
-    };
-    let update_time = match result_document.update_time {
-        Some(f) => Some(
-            chrono::DateTime::parse_from_rfc3339(&f)
-                .map_err(|_| FirebaseError::Generic("Failed to parse rfc3339 date from 'update_time' field"))?
-                .with_timezone(&chrono::Utc),
-        ),
-        None => None,
-    };
+    let create_time = parse_rfc3339(
+        result_document.create_time,
+        "Failed to parse rfc3339 date from 'create_time' field",
+    )?;
+    let update_time = parse_rfc3339(
+        result_document.update_time,
+        "Failed to parse rfc3339 date from 'update_time' field",
+    )?;
 
     Ok(WriteResult {
         document_id,
@@ -166,20 +347,40 @@ pub async fn write_async<T>(
 where
     T: Serialize,
 {
-    let mut url = match document_id.as_ref() {
-        Some(document_id) => firebase_url_extended(auth.project_id(), path, document_id.as_ref()),
-        None => firebase_url(auth.project_id(), path),
+    if options.precondition.is_some() && document_id.is_none() {
+        return Err(FirebaseError::Generic(
+            "A write with a precondition requires a document id",
+        ));
+    }
+
+    if !options.transforms.is_empty() {
+        let document_id = document_id.map_or_else(auto_id, |id| Ok(id.as_ref().to_owned()))?;
+        let mut batch = WriteBatch::new();
+        batch.set(path, document_id, document, options)?;
+        return batch
+            .commit_async(auth)
+            .await?
+            .pop()
+            .ok_or(FirebaseError::Generic("No write result in commit response"));
+    }
+
+    let url = match document_id.as_ref() {
+        Some(document_id) => firebase_url_extended(auth.project_id(), auth.database_id(), path, document_id.as_ref()),
+        None => firebase_url(auth.project_id(), auth.database_id(), path),
     };
 
-    let firebase_document = pod_to_document(&document)?;
+    let firebase_document = document_for_write(document, &options)?;
 
-    if options.merge && firebase_document.fields.is_some() {
-        let fields = firebase_document.fields.as_ref().unwrap().keys().join(",");
-        url = format!("{}?currentDocument.exists=true&updateMask.fieldPaths={}", url, fields);
+    let mut params: Vec<_> = options.precondition.iter().map(Precondition::query_param).collect();
+    if let Some(mask) = merge_mask(&firebase_document, &options)? {
+        params.extend(mask.into_iter().map(|field_path| ("updateMask.fieldPaths", field_path)));
+        if options.precondition.is_none() {
+            params.push(Precondition::Exists.query_param());
+        }
     }
 
     let builder = if document_id.is_some() {
-        auth.client_async().patch(&url)
+        auth.client_async().patch(&url).query(&params)
     } else {
         auth.client_async().post(&url)
     };
@@ -190,39 +391,282 @@ where
         .send()
         .await?;
 
-    let resp = extract_google_api_error_async(resp, || {
-        document_id.as_ref().map(|f| f.as_ref().to_owned()).unwrap_or_default()
-    })
-    .await?;
+    let context = || document_id.as_ref().map(|f| f.as_ref().to_owned()).unwrap_or_default();
+    // Merge writes have an implicit exists precondition
+    let resp = if options.precondition.is_some() || options.merge {
+        extract_google_api_error_precondition_async(resp, context).await?
+    } else {
+        extract_google_api_error_async(resp, context).await?
+    };
 
     let result_document: dto::Document = resp.json().await?;
-    let document_id = Path::new(&result_document.name)
+    let document_id = document_id_of_name(&result_document.name)?;
+
+    let create_time = parse_rfc3339(
+        result_document.create_time,
+        "Failed to parse rfc3339 date from 'create_time' field",
+    )?;
+    let update_time = parse_rfc3339(
+        result_document.update_time,
+        "Failed to parse rfc3339 date from 'update_time' field",
+    )?;
+
+    Ok(WriteResult {
+        document_id,
+        create_time,
+        update_time,
+    })
+}
+
+/// Joins the given field names to a field path like "profile.address.city".
+/// Field names that are not simple identifiers (letters, digits and underscores, not starting with a digit)
+/// are quoted with backticks.
+///
+/// Example:
+/// ```
+/// use firestore_db_and_auth::documents::field_path;
+///
+/// assert_eq!(field_path(&["profile", "address", "city"]), "profile.address.city");
+/// assert_eq!(field_path(&["scores", "2020.q1", "team `a`"]), r"scores.`2020.q1`.`team \`a\``");
+/// ```
+pub fn field_path(field_names: &[impl AsRef<str>]) -> String {
+    field_names
+        .iter()
+        .map(|name| quote_field_name(name.as_ref()))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Quotes a field name with backticks, if it is not a simple identifier
+fn quote_field_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let simple = match chars.next() {
+        Some(c) => (c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        None => false,
+    };
+    if simple {
+        return name.to_owned();
+    }
+    format!("`{}`", name.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+/// Collects the field paths of all leaf values of the given fields. Nested maps are descended into,
+/// so that a merge only updates the given nested fields. Empty maps are leaf values.
+fn leaf_field_paths(prefix: Option<&str>, fields: &HashMap<String, dto::Value>, paths: &mut Vec<String>) {
+    for (name, value) in fields {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, quote_field_name(name)),
+            None => quote_field_name(name),
+        };
+        match value.map_value.as_ref().and_then(|m| m.fields.as_ref()) {
+            Some(nested) if !nested.is_empty() => leaf_field_paths(Some(&path), nested, paths),
+            _ => paths.push(path),
+        }
+    }
+}
+
+/// Converts the document of a write, without the null fields if [`WriteOptions::skip_nulls`] is set
+pub(crate) fn document_for_write<T: Serialize>(document: &T, options: &WriteOptions) -> Result<dto::Document> {
+    let mut firebase_document = pod_to_document(document)?;
+    if options.skip_nulls {
+        if let Some(fields) = firebase_document.fields.as_mut() {
+            crate::values::remove_nulls(fields);
+            // An empty merge would replace the whole stored document, see merge_mask()
+            if fields.is_empty() && options.merge && options.update_mask.is_none() && options.transforms.is_empty() {
+                return Err(FirebaseError::Generic(
+                    "All fields of the merge write are null and skipped, there is nothing to write",
+                ));
+            }
+        }
+    }
+    Ok(firebase_document)
+}
+
+/// The update mask of a merge write. Either the explicit mask of the options or
+/// the field paths of all leaf values of the document. Returns None if the write is not a merge,
+/// and an error for an update mask without merge.
+///
+/// An empty mask without transforms is an error: Firestore would treat the write
+/// as a replacement of the whole document with an empty one.
+pub(crate) fn merge_mask(document: &dto::Document, options: &WriteOptions) -> Result<Option<Vec<String>>> {
+    if !options.merge {
+        if options.update_mask.is_some() {
+            return Err(FirebaseError::Generic("An update mask requires WriteOptions::merge"));
+        }
+        return Ok(None);
+    }
+    let paths = match options.update_mask.as_ref() {
+        Some(mask) => mask.clone(),
+        None => {
+            let mut paths = Vec::new();
+            if let Some(fields) = document.fields.as_ref() {
+                leaf_field_paths(None, fields, &mut paths);
+            }
+            paths.sort();
+            paths
+        }
+    };
+    if paths.is_empty() && options.transforms.is_empty() {
+        return Err(FirebaseError::Generic(
+            "A merge write requires at least one field, field path or transform",
+        ));
+    }
+    Ok(Some(paths))
+}
+
+/// Generates a random document id of 20 alphanumeric characters, like Firestore does
+fn auto_id() -> Result<String> {
+    use ring::rand::SecureRandom;
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+    let rng = ring::rand::SystemRandom::new();
+    let mut id = String::with_capacity(20);
+    let mut bytes = [0u8; 32];
+    while id.len() < 20 {
+        rng.fill(&mut bytes)
+            .map_err(|_| FirebaseError::Generic("Failed to generate a random document id"))?;
+        // Discard bytes above the largest multiple of the alphabet size to avoid a bias
+        let usable = bytes.iter().filter(|b| (**b as usize) < 256 - 256 % ALPHABET.len());
+        id.extend(
+            usable
+                .map(|b| ALPHABET[*b as usize % ALPHABET.len()] as char)
+                .take(20 - id.len()),
+        );
+    }
+    Ok(id)
+}
+
+/// Returns the last segment of a document name or path, the document id
+pub(crate) fn document_id_of_name(name: &str) -> Result<String> {
+    Ok(Path::new(name)
         .file_name()
         .ok_or_else(|| FirebaseError::Generic("Resulting documents 'name' field is not a valid path"))?
         .to_str()
         .ok_or_else(|| FirebaseError::Generic("No valid unicode in 'name' field"))?
-        .to_owned();
+        .to_owned())
+}
 
-    let create_time = match result_document.create_time {
-        Some(f) => Some(
+/// Parses an optional rfc3339 timestamp of the Firestore API. Returns the given error if parsing fails.
+pub(crate) fn parse_rfc3339(
+    value: Option<String>,
+    error: &'static str,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+    match value {
+        Some(f) => Ok(Some(
             chrono::DateTime::parse_from_rfc3339(&f)
-                .map_err(|_| FirebaseError::Generic("Failed to parse rfc3339 date from 'create_time' field"))?
+                .map_err(|_| FirebaseError::Generic(error))?
                 .with_timezone(&chrono::Utc),
-        ),
-        None => None,
+        )),
+        None => Ok(None),
+    }
+}
+
+#[test]
+fn it_generates_auto_ids() -> Result<()> {
+    let id = auto_id()?;
+    assert_eq!(id.len(), 20);
+    assert!(id.chars().all(|c| c.is_ascii_alphanumeric()));
+    assert_ne!(id, auto_id()?);
+    Ok(())
+}
+
+#[test]
+fn it_converts_preconditions() {
+    use chrono::TimeZone;
+    let time = chrono::Utc.timestamp_opt(1588085571, 250511000).unwrap();
+    let (key, value) = Precondition::UpdateTime(time).query_param();
+    assert_eq!(key, "currentDocument.updateTime");
+    assert_eq!(value, "2020-04-28T14:52:51.250511Z");
+
+    let precondition: dto::Precondition = (&Precondition::NotExists).into();
+    assert_eq!(precondition.exists, Some(false));
+    assert!(precondition.update_time.is_none());
+}
+
+#[test]
+fn it_builds_deep_merge_masks() -> Result<()> {
+    let document = pod_to_document(&serde_json::json!({
+        "name": "abc",
+        "profile": {"address": {"city": "Berlin", "zip code": "10115"}, "tags": [1, 2]},
+        "empty": {}
+    }))?;
+    let options = WriteOptions {
+        merge: true,
+        ..Default::default()
     };
-    let update_time = match result_document.update_time {
-        Some(f) => Some(
-            chrono::DateTime::parse_from_rfc3339(&f)
-                .map_err(|_| FirebaseError::Generic("Failed to parse rfc3339 date from 'update_time' field"))?
-                .with_timezone(&chrono::Utc),
-        ),
-        None => None,
+    assert_eq!(
+        merge_mask(&document, &options)?,
+        Some(vec![
+            "empty".to_owned(),
+            "name".to_owned(),
+            "profile.address.`zip code`".to_owned(),
+            "profile.address.city".to_owned(),
+            "profile.tags".to_owned(),
+        ])
+    );
+
+    let options = WriteOptions {
+        merge: true,
+        update_mask: Some(vec!["profile.age".to_owned()]),
+        ..Default::default()
     };
+    assert_eq!(merge_mask(&document, &options)?, Some(vec!["profile.age".to_owned()]));
+    assert_eq!(merge_mask(&document, &WriteOptions::default())?, None);
 
-    Ok(WriteResult {
-        document_id,
-        create_time,
-        update_time,
-    })
+    let options = WriteOptions {
+        update_mask: Some(vec!["profile.age".to_owned()]),
+        ..Default::default()
+    };
+    assert!(matches!(
+        merge_mask(&document, &options),
+        Err(FirebaseError::Generic(_))
+    ));
+    let mut batch = WriteBatch::new();
+    assert!(batch
+        .set("users", "alice", &serde_json::json!({"a": 1}), options)
+        .is_err());
+    assert!(batch.is_empty());
+
+    // An empty merge must never become an unmasked PATCH, which replaces the whole document
+    let empty = pod_to_document(&serde_json::json!({}))?;
+    let options = WriteOptions {
+        merge: true,
+        ..Default::default()
+    };
+    assert!(matches!(merge_mask(&empty, &options), Err(FirebaseError::Generic(_))));
+    let options = WriteOptions {
+        merge: true,
+        update_mask: Some(vec![]),
+        ..Default::default()
+    };
+    assert!(matches!(
+        merge_mask(&document, &options),
+        Err(FirebaseError::Generic(_))
+    ));
+    let options = WriteOptions {
+        merge: true,
+        transforms: vec![dto::FieldTransform::request_time("updated")],
+        ..Default::default()
+    };
+    assert_eq!(merge_mask(&empty, &options)?, Some(vec![]));
+
+    let document = serde_json::json!({"name": "abc", "profile": {"age": null}});
+    let options = WriteOptions {
+        merge: true,
+        skip_nulls: true,
+        ..Default::default()
+    };
+    let skipped = document_for_write(&document, &options)?;
+    assert_eq!(merge_mask(&skipped, &options)?, Some(vec!["name".to_owned()]));
+    let all_nulls = serde_json::json!({"name": null, "profile": {"age": null}});
+    assert!(matches!(
+        document_for_write(&all_nulls, &options),
+        Err(FirebaseError::Generic(_))
+    ));
+    let nulls = document_for_write(&document, &WriteOptions::default())?;
+    assert_eq!(
+        merge_mask(&nulls, &options)?,
+        Some(vec!["name".to_owned(), "profile.age".to_owned()])
+    );
+    Ok(())
 }
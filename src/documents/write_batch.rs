@@ -0,0 +1,356 @@
+use super::*;
+use crate::errors::{
+    extract_google_api_error_async, extract_google_api_error_precondition, extract_google_api_error_precondition_async,
+};
+use crate::FirebaseAuthBearerAsync;
+
+/// A staged write of a [`WriteBatch`]. The document name is resolved on commit,
+/// when the project id of the authentication token is known.
+struct BatchWrite {
+    /// The relative collection path and document id, for example "my_collection/document_id"
+    path: String,
+    write: dto::Write,
+}
+
+///
+/// Collects document writes and deletes and applies them atomically in one request.
+///
+/// Either all writes succeed or none is applied. A batch may contain up to 500 writes.
+///
+/// Example:
+/// ```no_run
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct DemoDTO { a_string: String, an_int: u32, }
+///
+/// use firestore_db_and_auth::documents::{WriteBatch, WriteOptions};
+/// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+/// # let session = BlockingServiceSession::new(doctest_credentials())?;
+///
+/// let obj = DemoDTO { a_string: "abcd".to_owned(), an_int: 14 };
+/// let mut batch = WriteBatch::new();
+/// batch
+///     .set("tests", "first", &obj, WriteOptions::default())?
+///     .update("tests", "second", &obj)?
+///     .delete("tests/third");
+/// let results = batch.commit(&session)?;
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+#[derive(Default)]
+pub struct WriteBatch {
+    writes: Vec<BatchWrite>,
+}
+
+impl WriteBatch {
+    /// Creates an empty batch
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The number of staged writes
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    /// Returns true if no writes are staged
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Stages a write of the given document. Works like [`write()`] with a document id.
+    ///
+    /// ## Arguments
+    /// * 'path' The document path / collection; For example "my_collection" or "a/nested/collection"
+    /// * 'document_id' The document id. Make sure that you do not include the document id in the path argument.
+    /// * 'document' The document
+    /// * 'options' Write options
+    pub fn set<T>(
+        &mut self,
+        path: &str,
+        document_id: impl AsRef<str>,
+        document: &T,
+        options: WriteOptions,
+    ) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        let firebase_document = document_for_write(document, &options)?;
+
+        let mut write = dto::Write {
+            current_document: options.precondition.as_ref().map(Into::into),
+            ..Default::default()
+        };
+        if let Some(field_paths) = merge_mask(&firebase_document, &options)? {
+            write.update_mask = Some(dto::DocumentMask { field_paths });
+            if write.current_document.is_none() {
+                write.current_document = Some((&Precondition::Exists).into());
+            }
+        }
+        if !options.transforms.is_empty() {
+            write.update_transforms = Some(options.transforms);
+        }
+        write.update = Some(firebase_document);
+
+        self.writes.push(BatchWrite {
+            path: format!("{}/{}", path, document_id.as_ref()),
+            write,
+        });
+        Ok(self)
+    }
+
+    /// Stages a merge of the fields of the given document into an existing document.
+    /// This is a shortcut for [`WriteBatch::set`] with [`WriteOptions::merge`] set.
+    /// The whole batch fails, if the document does not exist.
+    ///
+    /// ## Arguments
+    /// * 'path' The document path / collection; For example "my_collection" or "a/nested/collection"
+    /// * 'document_id' The document id. Make sure that you do not include the document id in the path argument.
+    /// * 'document' The document with the fields to update
+    pub fn update<T>(&mut self, path: &str, document_id: impl AsRef<str>, document: &T) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        self.set(
+            path,
+            document_id,
+            document,
+            WriteOptions {
+                merge: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Stages the deletion of a document. Deleting a document that does not exist is not an error.
+    ///
+    /// ## Arguments
+    /// * 'path' The relative collection path and document id, for example "my_collection/document_id"
+    pub fn delete(&mut self, path: &str) -> &mut Self {
+        self.writes.push(BatchWrite {
+            path: path.to_owned(),
+            write: dto::Write::default(),
+        });
+        self
+    }
+
+    /// Stages the deletion of a document, if the given precondition is met.
+    /// Otherwise the whole batch fails with [`FirebaseError::PreconditionFailed`].
+    ///
+    /// ## Arguments
+    /// * 'path' The relative collection path and document id, for example "my_collection/document_id"
+    /// * 'precondition' The condition on the document
+    pub fn delete_with_precondition(&mut self, path: &str, precondition: Precondition) -> &mut Self {
+        self.writes.push(BatchWrite {
+            path: path.to_owned(),
+            write: dto::Write {
+                current_document: Some((&precondition).into()),
+                ..Default::default()
+            },
+        });
+        self
+    }
+
+    /// Applies all staged writes atomically.
+    ///
+    /// Returns one [`WriteResult`] per staged write, in the order the writes were staged.
+    /// The `create_time` of the results is not known and always `None`.
+    /// The `update_time` of a delete is `None` as well.
+    ///
+    /// ## Arguments
+    /// * 'auth' The authentication token
+    pub fn commit(self, auth: &impl FirebaseAuthBearer) -> Result<Vec<WriteResult>> {
+        let paths: Vec<String> = self.writes.iter().map(|w| w.path.clone()).collect();
+        let writes = self.into_writes(auth.project_id(), auth.database_id());
+        let response = commit_writes(auth, writes, None)?;
+        write_results(&paths, response)
+    }
+
+    /// Applies all staged writes atomically.
+    ///
+    /// Returns one [`WriteResult`] per staged write, in the order the writes were staged.
+    /// The `create_time` of the results is not known and always `None`.
+    /// The `update_time` of a delete is `None` as well.
+    ///
+    /// ## Arguments
+    /// * 'auth' The authentication token
+    ///
+    /// THIS IS A NON-BLOCKING OPERATION
+    pub async fn commit_async(self, auth: &mut impl FirebaseAuthBearerAsync) -> Result<Vec<WriteResult>> {
+        let paths: Vec<String> = self.writes.iter().map(|w| w.path.clone()).collect();
+        let writes = self.into_writes(auth.project_id(), auth.database_id());
+        let response = commit_writes_async(auth, writes, None).await?;
+        write_results(&paths, response)
+    }
+
+    /// Resolves the document names of all staged writes
+    pub(crate) fn into_writes(self, project_id: &str, database_id: &str) -> Vec<dto::Write> {
+        self.writes
+            .into_iter()
+            .map(|BatchWrite { path, mut write }| {
+                let name = firebase_document_name(project_id, database_id, &path);
+                match write.update.as_mut() {
+                    Some(document) => document.name = name,
+                    None => write.delete = Some(name),
+                }
+                write
+            })
+            .collect()
+    }
+}
+
+/// Sends the given writes to the commit endpoint, optionally as part of a transaction
+pub(crate) fn commit_writes(
+    auth: &impl FirebaseAuthBearer,
+    writes: Vec<dto::Write>,
+    transaction: Option<String>,
+) -> Result<dto::CommitResponse> {
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "commit");
+    let request = dto::CommitRequest {
+        writes: Some(writes),
+        transaction,
+    };
+
+    let resp = auth
+        .client()
+        .post(url)
+        .bearer_auth(auth.access_token().to_owned())
+        .json(&request)
+        .send()?;
+
+    let resp = match has_precondition(&request) {
+        true => extract_google_api_error_precondition(resp, || commit_context(&request))?,
+        false => extract_google_api_error(resp, || commit_context(&request))?,
+    };
+
+    Ok(resp.json()?)
+}
+
+/// Sends the given writes to the commit endpoint, optionally as part of a transaction
+pub(crate) async fn commit_writes_async(
+    auth: &mut impl FirebaseAuthBearerAsync,
+    writes: Vec<dto::Write>,
+    transaction: Option<String>,
+) -> Result<dto::CommitResponse> {
+    let url = firebase_url_method(auth.project_id(), auth.database_id(), None, "commit");
+    let request = dto::CommitRequest {
+        writes: Some(writes),
+        transaction,
+    };
+
+    let resp = auth
+        .client_async()
+        .post(&url)
+        .bearer_auth(auth.access_token().await.to_string())
+        .json(&request)
+        .send()
+        .await?;
+
+    let resp = match has_precondition(&request) {
+        true => extract_google_api_error_precondition_async(resp, || commit_context(&request)).await?,
+        false => extract_google_api_error_async(resp, || commit_context(&request)).await?,
+    };
+
+    Ok(resp.json().await?)
+}
+
+fn has_precondition(request: &dto::CommitRequest) -> bool {
+    request.writes.iter().flatten().any(|w| w.current_document.is_some())
+}
+
+/// The names of all written documents, for error messages
+fn commit_context(request: &dto::CommitRequest) -> String {
+    request
+        .writes
+        .iter()
+        .flatten()
+        .filter_map(|w| w.update.as_ref().map(|d| d.name.as_str()).or(w.delete.as_deref()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn write_results(paths: &[String], response: dto::CommitResponse) -> Result<Vec<WriteResult>> {
+    let results = response.write_results.unwrap_or_default();
+    if results.len() != paths.len() {
+        return Err(FirebaseError::Generic(
+            "The number of write results does not match the number of writes",
+        ));
+    }
+
+    paths
+        .iter()
+        .zip(results)
+        .map(|(path, result)| {
+            Ok(WriteResult {
+                document_id: document_id_of_name(path)?,
+                create_time: None,
+                update_time: parse_rfc3339(
+                    result.update_time,
+                    "Failed to parse rfc3339 date from 'update_time' field",
+                )?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Demo {
+        a: i64,
+    }
+
+    #[test]
+    fn it_resolves_batch_writes() -> Result<()> {
+        let mut batch = WriteBatch::new();
+        batch
+            .set("tests", "one", &Demo { a: 1 }, WriteOptions::default())?
+            .update("tests", "two", &Demo { a: 2 })?
+            .delete("tests/three")
+            .set(
+                "tests",
+                "four",
+                &Demo { a: 4 },
+                WriteOptions {
+                    transforms: vec![dto::FieldTransform::increment("b", 1)],
+                    ..Default::default()
+                },
+            )?;
+        assert_eq!(batch.len(), 4);
+
+        let writes = serde_json::to_value(batch.into_writes("p", DEFAULT_DATABASE_ID))?;
+        assert_eq!(
+            writes[0]["update"]["name"],
+            "projects/p/databases/(default)/documents/tests/one"
+        );
+        assert!(writes[0].get("updateMask").is_none());
+        assert_eq!(writes[1]["updateMask"]["fieldPaths"], serde_json::json!(["a"]));
+        assert_eq!(writes[1]["currentDocument"]["exists"], true);
+        assert_eq!(
+            writes[2]["delete"],
+            "projects/p/databases/(default)/documents/tests/three"
+        );
+        assert_eq!(
+            writes[3]["updateTransforms"],
+            serde_json::json!([{"fieldPath": "b", "increment": {"integerValue": "1"}}])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn it_maps_write_results() -> Result<()> {
+        let response: dto::CommitResponse = serde_json::from_str(
+            r#"{
+            "writeResults": [{"updateTime": "2020-04-28T14:52:51.250511Z"}, {}],
+            "commitTime": "2020-04-28T14:52:51.250511Z"
+            }"#,
+        )?;
+        let results = write_results(&["tests/one".to_owned(), "tests/two".to_owned()], response)?;
+        assert_eq!(results[0].document_id, "one");
+        assert!(results[0].update_time.is_some());
+        assert_eq!(results[1].document_id, "two");
+        assert!(results[1].update_time.is_none());
+        Ok(())
+    }
+}
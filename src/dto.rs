@@ -44,6 +44,9 @@ pub struct Write {
     pub update: Option<Document>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transform: Option<DocumentTransform>,
+    #[serde(rename = "updateTransforms")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_transforms: Option<Vec<FieldTransform>>,
     #[serde(rename = "updateMask")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub update_mask: Option<DocumentMask>,
@@ -60,7 +63,11 @@ pub enum FieldOperator {
     GREATER_THAN,          //	Greater than. Requires that the field come first in orderBy.
     GREATER_THAN_OR_EQUAL, //	Greater than or equal. Requires that the field come first in orderBy.
     EQUAL,                 //	Equal.
+    NOT_EQUAL,             //	Not equal. Requires that the field come first in orderBy.
     ARRAY_CONTAINS,        //	Contains. Requires that the field is an array.
+    IN,                    //	In. Requires that value is a non-empty array with at most 30 values.
+    ARRAY_CONTAINS_ANY, //	Contains any. Requires that the field is an array and value is a non-empty array with at most 30 values.
+    NOT_IN,             //	Not in. Requires that value is a non-empty array with at most 10 values.
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -136,16 +143,95 @@ pub struct RunQueryRequest {
     pub read_time: Option<String>,
 }
 
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct RunAggregationQueryRequest {
+    #[serde(rename = "newTransaction")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_transaction: Option<TransactionOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<String>,
+    #[serde(rename = "structuredAggregationQuery")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_aggregation_query: Option<StructuredAggregationQuery>,
+    #[serde(rename = "readTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_time: Option<String>,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct StructuredAggregationQuery {
+    #[serde(rename = "structuredQuery")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_query: Option<StructuredQuery>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregations: Option<Vec<Aggregation>>,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Aggregation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sum: Option<Sum>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg: Option<Avg>,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Count {
+    #[serde(rename = "upTo")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub up_to: Option<String>,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Sum {
+    pub field: FieldReference,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Avg {
+    pub field: FieldReference,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct RunAggregationQueryResponse {
+    pub result: Option<AggregationResult>,
+    pub transaction: Option<String>,
+    #[serde(rename = "readTime")]
+    pub read_time: Option<String>,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct AggregationResult {
+    #[serde(rename = "aggregateFields")]
+    pub aggregate_fields: Option<HashMap<String, Value>>,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct FieldReference {
     #[serde(rename = "fieldPath")]
     pub field_path: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+#[derive(Default)]
+pub enum UnaryOperator {
+    #[default]
+    OPERATOR_UNSPECIFIED, //	Unspecified. This value must not be used.
+    IS_NAN,      //	The given field is equal to NaN.
+    IS_NULL,     //	The given field is equal to NULL.
+    IS_NOT_NAN,  //	The given field is not equal to NaN.
+    IS_NOT_NULL, //	The given field is not equal to NULL.
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct UnaryFilter {
     pub field: FieldReference,
-    pub op: String,
+    pub op: UnaryOperator,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -159,10 +245,20 @@ pub struct DocumentMask {
     pub field_paths: Vec<String>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+#[derive(Default)]
+pub enum CompositeOperator {
+    #[default]
+    OPERATOR_UNSPECIFIED, //	Unspecified. This value must not be used.
+    AND, //	Documents are required to satisfy all of the combined filters.
+    OR,  //	Documents are required to satisfy at least one of the combined filters.
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct CompositeFilter {
     pub filters: Vec<Filter>,
-    pub op: String,
+    pub op: CompositeOperator,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -198,8 +294,10 @@ pub struct WriteResponse {
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct ListCollectionIdsRequest {
     #[serde(rename = "pageToken")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page_token: Option<String>,
     #[serde(rename = "pageSize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<i32>,
 }
 
@@ -227,8 +325,10 @@ pub struct MapValue {
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct TransactionOptions {
     #[serde(rename = "readWrite")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub read_write: Option<ReadWrite>,
     #[serde(rename = "readOnly")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub read_only: Option<ReadOnly>,
 }
 
@@ -294,6 +394,7 @@ pub struct Value {
 
     #[serde(rename = "doubleValue")]
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, with = "double_value")]
     pub double_value: Option<f64>,
 
     #[serde(rename = "mapValue")]
@@ -316,11 +417,26 @@ pub struct Value {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub integer_value: Option<String>,
 
+    /// Set to [`NULL_VALUE`] for null. Sent and received as `"nullValue": null`.
     #[serde(rename = "nullValue")]
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, with = "null_value")]
     pub null_value: Option<String>,
 }
 
+/// The only variant of the `nullValue` enum of the Firestore API
+pub const NULL_VALUE: &str = "NULL_VALUE";
+
+impl Value {
+    /// A Firestore `nullValue`
+    pub fn null() -> Self {
+        Value {
+            null_value: Some(NULL_VALUE.to_owned()),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct Cursor {
     pub values: Option<Vec<Value>>,
@@ -372,11 +488,29 @@ pub struct FieldTransform {
     #[serde(rename = "fieldPath")]
     pub field_path: Option<String>,
     #[serde(rename = "appendMissingElements")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub append_missing_elements: Option<ArrayValue>,
     #[serde(rename = "setToServerValue")]
-    pub set_to_server_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_to_server_value: Option<ServerValue>,
     #[serde(rename = "removeAllFromArray")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub remove_all_from_array: Option<ArrayValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub increment: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<Value>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+#[derive(Default)]
+pub enum ServerValue {
+    #[default]
+    SERVER_VALUE_UNSPECIFIED, //	Unspecified. This value must not be used.
+    REQUEST_TIME, //	The time at which the server processed the request, with millisecond precision.
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -399,12 +533,22 @@ pub struct GoogleFirestoreAdminv1ExportDocumentsRequest {
     pub collection_ids: Option<Vec<String>>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+#[derive(Default)]
+pub enum Direction {
+    #[default]
+    DIRECTION_UNSPECIFIED, //	Unspecified.
+    ASCENDING,  //	Ascending.
+    DESCENDING, //	Descending.
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct Order {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub field: Option<FieldReference>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub direction: Option<String>,
+    pub direction: Option<Direction>,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -441,6 +585,7 @@ pub struct ListCollectionIdsResponse {
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct CommitRequest {
     pub writes: Option<Vec<Write>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub transaction: Option<String>,
 }
 
@@ -459,6 +604,7 @@ pub struct ListDocumentsResponse {
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct ReadWrite {
     #[serde(rename = "retryTransaction")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_transaction: Option<String>,
 }
 
@@ -503,6 +649,7 @@ pub struct RollbackRequest {
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct ReadOnly {
     #[serde(rename = "readTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub read_time: Option<String>,
 }
 
@@ -571,8 +718,87 @@ pub struct OAuthResponse {
     pub raw_user_info: String,
 }
 
+/// The json encoding of `doubleValue`. NaN and infinity are not json numbers,
+/// but the strings "NaN", "Infinity" and "-Infinity".
+mod double_value {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match v {
+            Some(v) if v.is_nan() => serializer.serialize_str("NaN"),
+            Some(v) if *v == f64::INFINITY => serializer.serialize_str("Infinity"),
+            Some(v) if *v == f64::NEG_INFINITY => serializer.serialize_str("-Infinity"),
+            Some(v) => serializer.serialize_f64(*v),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Double {
+        Number(f64),
+        String(String),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+        match Option::<Double>::deserialize(deserializer)? {
+            Some(Double::Number(v)) => Ok(Some(v)),
+            Some(Double::String(v)) => match v.as_str() {
+                "NaN" => Ok(Some(f64::NAN)),
+                "Infinity" => Ok(Some(f64::INFINITY)),
+                "-Infinity" => Ok(Some(f64::NEG_INFINITY)),
+                _ => v
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| de::Error::custom(format!("Invalid double value '{}'", v))),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// The json encoding of `nullValue`. Its only value is encoded as json `null`, which must not be read as absent.
+mod null_value {
+    use serde::{de::IgnoredAny, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(_v: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(Some(super::NULL_VALUE.to_owned()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn it_encodes_nan_and_infinity_doubles() {
+        for v in &[f64::INFINITY, f64::NEG_INFINITY, 1.5] {
+            let value = crate::dto::Value {
+                double_value: Some(*v),
+                ..Default::default()
+            };
+            let json = serde_json::to_string(&value).unwrap();
+            let value: crate::dto::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(value.double_value, Some(*v));
+        }
+        let value: crate::dto::Value = serde_json::from_str(r#"{"doubleValue": "NaN"}"#).unwrap();
+        assert!(value.double_value.unwrap().is_nan());
+        assert_eq!(serde_json::to_string(&value).unwrap(), r#"{"doubleValue":"NaN"}"#);
+    }
+
+    #[test]
+    fn it_encodes_null_values() {
+        let json = serde_json::to_string(&crate::dto::Value::null()).unwrap();
+        assert_eq!(json, r#"{"nullValue":null}"#);
+        let value: crate::dto::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value.null_value.as_deref(), Some(crate::dto::NULL_VALUE));
+        let value: crate::dto::Value = serde_json::from_str("{}").unwrap();
+        assert!(value.null_value.is_none());
+    }
+
     #[test]
     fn it_deserializes_a_document_with_empty_mapvalue() {
         let doc = r#"{
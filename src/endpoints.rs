@@ -0,0 +1,107 @@
+//! # Service Endpoints
+//!
+//! The base urls of the Google APIs that are used by this crate.
+//!
+//! By default the Google production hosts are used. If the environment variable
+//! `FIRESTORE_EMULATOR_HOST` is set (for example to "localhost:8080"), all Firestore requests are sent
+//! to the [Firestore emulator](https://firebase.google.com/docs/emulator-suite) instead.
+//! The same is true for `FIREBASE_AUTH_EMULATOR_HOST` and all Firebase Auth requests.
+//!
+//! The Firestore emulator does not verify access tokens. Use [`crate::sessions::emulator::OwnerSession`]
+//! to access the emulator with the "owner" token, which bypasses all security rules.
+//!
+//! Call [`set_endpoints`] to configure the endpoints independent of the environment.
+
+use std::sync::RwLock;
+
+/// The environment variable with the host and port of the Firestore emulator, like "localhost:8080"
+pub static FIRESTORE_EMULATOR_HOST: &str = "FIRESTORE_EMULATOR_HOST";
+/// The environment variable with the host and port of the Firebase Auth emulator, like "localhost:9099"
+pub static FIREBASE_AUTH_EMULATOR_HOST: &str = "FIREBASE_AUTH_EMULATOR_HOST";
+
+/// The base urls of the Google APIs, without a trailing slash
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoints {
+    /// The Firestore API including the version, for example "https://firestore.googleapis.com/v1"
+    pub firestore: String,
+    /// The Identity Toolkit API, for example "https://identitytoolkit.googleapis.com"
+    pub identity_toolkit: String,
+    /// The Secure Token API that exchanges refresh tokens, for example "https://securetoken.googleapis.com"
+    pub secure_token: String,
+    /// Other Google APIs like the legacy Identity Toolkit and the service account JWK sets,
+    /// for example "https://www.googleapis.com"
+    pub google_apis: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Endpoints {
+            firestore: "https://firestore.googleapis.com/v1".to_owned(),
+            identity_toolkit: "https://identitytoolkit.googleapis.com".to_owned(),
+            secure_token: "https://securetoken.googleapis.com".to_owned(),
+            google_apis: "https://www.googleapis.com".to_owned(),
+        }
+    }
+}
+
+impl Endpoints {
+    /// The endpoints of the emulators on the given hosts, like "localhost:8080".
+    /// Services without an emulator host use the production endpoints.
+    ///
+    /// ## Arguments
+    /// * 'firestore_host' The host and port of the Firestore emulator
+    /// * 'auth_host' The host and port of the Firebase Auth emulator
+    pub fn emulator(firestore_host: Option<&str>, auth_host: Option<&str>) -> Self {
+        let mut endpoints = Endpoints::default();
+        if let Some(host) = firestore_host {
+            endpoints.firestore = format!("http://{}/v1", host);
+        }
+        // The auth emulator serves all auth APIs, prefixed by their production host name
+        if let Some(host) = auth_host {
+            endpoints.identity_toolkit = format!("http://{}/identitytoolkit.googleapis.com", host);
+            endpoints.secure_token = format!("http://{}/securetoken.googleapis.com", host);
+            endpoints.google_apis = format!("http://{}/www.googleapis.com", host);
+        }
+        endpoints
+    }
+
+    /// The endpoints as configured by the `FIRESTORE_EMULATOR_HOST` and `FIREBASE_AUTH_EMULATOR_HOST`
+    /// environment variables. Those are the production endpoints if no variable is set.
+    pub fn from_env() -> Self {
+        let firestore_host = std::env::var(FIRESTORE_EMULATOR_HOST).ok().filter(|h| !h.is_empty());
+        let auth_host = std::env::var(FIREBASE_AUTH_EMULATOR_HOST)
+            .ok()
+            .filter(|h| !h.is_empty());
+        Endpoints::emulator(firestore_host.as_deref(), auth_host.as_deref())
+    }
+}
+
+static ENDPOINTS: RwLock<Option<Endpoints>> = RwLock::new(None);
+
+/// Replaces the endpoints for all following requests. The environment variables are not considered anymore.
+/// Pass `None` to go back to the endpoints of the environment.
+pub fn set_endpoints(endpoints: Option<Endpoints>) {
+    *ENDPOINTS.write().unwrap_or_else(|e| e.into_inner()) = endpoints;
+}
+
+/// The endpoints given to [`set_endpoints`], or the endpoints of the environment, see [`Endpoints::from_env`]
+pub fn endpoints() -> Endpoints {
+    match ENDPOINTS.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(endpoints) => endpoints.clone(),
+        None => Endpoints::from_env(),
+    }
+}
+
+#[test]
+fn it_builds_emulator_endpoints() {
+    let endpoints = Endpoints::emulator(Some("localhost:8080"), None);
+    assert_eq!(endpoints.firestore, "http://localhost:8080/v1");
+    assert_eq!(endpoints.identity_toolkit, Endpoints::default().identity_toolkit);
+
+    let endpoints = Endpoints::emulator(None, Some("localhost:9099"));
+    assert_eq!(endpoints.firestore, Endpoints::default().firestore);
+    assert_eq!(
+        endpoints.secure_token,
+        "http://localhost:9099/securetoken.googleapis.com"
+    );
+}
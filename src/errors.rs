@@ -47,6 +47,21 @@ pub enum FirebaseError {
     IO(std::io::Error),
     /// Invalid UTF8 encoding errors
     Utf8(std::str::Utf8Error),
+    /// A query was rejected before it was sent, because it would be refused by Firestore anyway.
+    /// For example an `IN` filter with more than 30 values.
+    InvalidQuery(String),
+    /// The Firestore API aborted the request because of a conflict with a concurrent operation,
+    /// usually a concurrent transaction. Contains the message and the context, see [`FirebaseError::APIError`].
+    /// The operation can be retried.
+    Aborted(String, String),
+    /// A precondition of a write or delete was not met. For example the document has been modified
+    /// since the given update time, or it already exists although it should be created.
+    /// Contains the message and the context, see [`FirebaseError::APIError`].
+    /// Only returned if a precondition was given explicitly or implied by a merge write, and by batch and transaction commits.
+    PreconditionFailed(String, String),
+    /// A collection or document path is invalid. For example it has empty segments,
+    /// or a document path was given where a collection path is expected.
+    InvalidPath(String),
 }
 
 impl std::convert::From<std::io::Error> for FirebaseError {
@@ -73,6 +88,15 @@ impl std::convert::From<serde_json::Error> for FirebaseError {
     }
 }
 
+impl std::convert::From<crate::values::Error> for FirebaseError {
+    fn from(error: crate::values::Error) -> Self {
+        FirebaseError::Ser {
+            doc: None,
+            ser: serde::de::Error::custom(error),
+        }
+    }
+}
+
 impl std::convert::From<biscuit::errors::Error> for FirebaseError {
     fn from(error: biscuit::errors::Error) -> Self {
         FirebaseError::JWT(error)
@@ -117,6 +141,12 @@ impl fmt::Display for FirebaseError {
                 }
             }
             FirebaseError::Utf8(ref e) => e.fmt(f),
+            FirebaseError::InvalidQuery(ref m) => write!(f, "Invalid query: {}", m),
+            FirebaseError::Aborted(ref m, ref context) => write!(f, "Aborted: {}. Context: {}", m, context),
+            FirebaseError::InvalidPath(ref m) => write!(f, "Invalid path: {}", m),
+            FirebaseError::PreconditionFailed(ref m, ref context) => {
+                write!(f, "Precondition failed: {}. Context: {}", m, context)
+            }
         }
     }
 }
@@ -134,6 +164,10 @@ impl error::Error for FirebaseError {
             FirebaseError::IO(ref e) => Some(e),
             FirebaseError::Ser { ref ser, .. } => Some(ser),
             FirebaseError::Utf8(ref e) => Some(e),
+            FirebaseError::InvalidQuery(_) => None,
+            FirebaseError::Aborted(_, _) => None,
+            FirebaseError::PreconditionFailed(_, _) => None,
+            FirebaseError::InvalidPath(_) => None,
         }
     }
 }
@@ -149,6 +183,8 @@ struct GoogleRESTApiError {
 struct GoogleRESTApiErrorInfo {
     pub code: usize,
     pub message: String,
+    /// The canonical error code of the Firestore API, for example "ABORTED"
+    pub status: Option<String>,
     pub errors: Option<Vec<GoogleRESTApiError>>,
 }
 
@@ -176,6 +212,25 @@ pub(crate) fn extract_google_api_error(
         response.status(),
         response.text()?,
         context,
+        false,
+    ))
+}
+
+/// Like [`extract_google_api_error`], but for requests with an explicit precondition.
+/// Errors caused by an unmet precondition are returned as [`FirebaseError::PreconditionFailed`].
+pub(crate) fn extract_google_api_error_precondition(
+    response: reqwest::blocking::Response,
+    context: impl Fn() -> String,
+) -> Result<reqwest::blocking::Response> {
+    if response.status() == 200 {
+        return Ok(response);
+    }
+
+    Err(extract_google_api_error_intern(
+        response.status(),
+        response.text()?,
+        context,
+        true,
     ))
 }
 
@@ -198,6 +253,25 @@ pub(crate) async fn extract_google_api_error_async(
         response.status(),
         response.text().await?,
         context,
+        false,
+    ))
+}
+
+/// Like [`extract_google_api_error_async`], but for requests with an explicit precondition.
+/// Errors caused by an unmet precondition are returned as [`FirebaseError::PreconditionFailed`].
+pub(crate) async fn extract_google_api_error_precondition_async(
+    response: reqwest::Response,
+    context: impl Fn() -> String,
+) -> Result<reqwest::Response> {
+    if response.status() == 200 {
+        return Ok(response);
+    }
+
+    Err(extract_google_api_error_intern(
+        response.status(),
+        response.text().await?,
+        context,
+        true,
     ))
 }
 
@@ -205,14 +279,47 @@ fn extract_google_api_error_intern(
     status: StatusCode,
     http_body: String,
     context: impl Fn() -> String,
+    precondition: bool,
 ) -> FirebaseError {
     let google_api_error_wrapper: std::result::Result<GoogleRESTApiErrorWrapper, serde_json::Error> =
         serde_json::from_str(&http_body);
     if let Ok(google_api_error_wrapper) = google_api_error_wrapper {
         if let Some(google_api_error) = google_api_error_wrapper.error {
+            if google_api_error.status.as_deref() == Some("ABORTED") {
+                return FirebaseError::Aborted(google_api_error.message, context());
+            }
+            // A missing document (exists or update time precondition) or an existing document
+            // (not exists precondition) are reported with their own status codes
+            let precondition_status = matches!(
+                google_api_error.status.as_deref(),
+                Some("FAILED_PRECONDITION") | Some("ALREADY_EXISTS") | Some("NOT_FOUND")
+            );
+            if precondition && precondition_status {
+                return FirebaseError::PreconditionFailed(google_api_error.message, context());
+            }
             return FirebaseError::APIError(google_api_error.code, google_api_error.message.to_owned(), context());
         }
     };
 
     FirebaseError::UnexpectedResponse("", status, http_body, context())
 }
+
+#[test]
+fn it_maps_google_api_error_status() {
+    let body =
+        r#"{"error": {"code": 400, "message": "the stored version does not match", "status": "FAILED_PRECONDITION"}}"#;
+    let context = || "tests/doc".to_owned();
+    let error = extract_google_api_error_intern(StatusCode::BAD_REQUEST, body.to_owned(), context, true);
+    assert!(matches!(error, FirebaseError::PreconditionFailed(_, ref c) if c == "tests/doc"));
+    let error = extract_google_api_error_intern(StatusCode::BAD_REQUEST, body.to_owned(), context, false);
+    assert!(matches!(error, FirebaseError::APIError(400, _, _)));
+
+    // A merge on a missing document
+    let body = r#"{"error": {"code": 404, "message": "No document to update", "status": "NOT_FOUND"}}"#;
+    let error = extract_google_api_error_intern(StatusCode::NOT_FOUND, body.to_owned(), context, true);
+    assert!(matches!(error, FirebaseError::PreconditionFailed(_, _)));
+
+    let body = r#"{"error": {"code": 409, "message": "too much contention", "status": "ABORTED"}}"#;
+    let error = extract_google_api_error_intern(StatusCode::CONFLICT, body.to_owned(), context, false);
+    assert!(matches!(error, FirebaseError::Aborted(_, _)));
+}
@@ -4,108 +4,52 @@
 //! and deeply nested and wrapped.
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::HashMap;
+use std::convert::TryFrom;
 
 use super::dto;
 use super::errors::{FirebaseError, Result};
+use super::values;
 
-#[derive(Serialize, Deserialize)]
-struct Wrapper {
-    #[serde(flatten)]
-    extra: HashMap<String, Value>,
-}
+/// Fails for values that Firestore can not store, for example integers above `i64::MAX`
+impl TryFrom<serde_json::Value> for dto::Value {
+    type Error = FirebaseError;
 
-use serde_json::{map::Map, Number};
+    fn try_from(v: serde_json::Value) -> Result<Self> {
+        values::to_value(&v)
+    }
+}
 
-/// Converts a firebase google-rpc-api inspired heavily nested and wrapped response value
-/// of the Firebase REST API into a flattened serde json value.
-///
-/// This is a low level API. You probably want to use [`crate::documents`] instead.
-///
-/// This method works recursively!
-pub(crate) fn firebase_value_to_serde_value(v: &dto::Value) -> serde_json::Value {
-    if let Some(timestamp_value) = v.timestamp_value.as_ref() {
-        return Value::String(timestamp_value.clone());
-    } else if let Some(integer_value) = v.integer_value.as_ref() {
-        if let Ok(four) = integer_value.parse::<i64>() {
-            return Value::Number(four.into());
-        }
-    } else if let Some(double_value) = v.double_value {
-        if let Some(dd) = Number::from_f64(double_value) {
-            return Value::Number(dd);
-        }
-    } else if let Some(map_value) = v.map_value.as_ref() {
-        let mut map: Map<String, serde_json::value::Value> = Map::new();
-        if let Some(map_fields) = &map_value.fields {
-            for (map_key, map_v) in map_fields {
-                map.insert(map_key.clone(), firebase_value_to_serde_value(map_v));
+/// Only for types that always convert, see [`values::to_value`] for all other types
+macro_rules! impl_from_for_value {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for dto::Value {
+                fn from(v: $t) -> Self {
+                    values::to_value(&v).expect(concat!("A ", stringify!($t), " is always a valid value"))
+                }
             }
-        }
-        return Value::Object(map);
-    } else if let Some(string_value) = v.string_value.as_ref() {
-        return Value::String(string_value.clone());
-    } else if let Some(boolean_value) = v.boolean_value {
-        return Value::Bool(boolean_value);
-    } else if let Some(array_value) = v.array_value.as_ref() {
-        let mut vec: Vec<Value> = Vec::new();
-        if let Some(values) = &array_value.values {
-            for k in values {
-                vec.push(firebase_value_to_serde_value(k));
-            }
-        }
-        return Value::Array(vec);
-    }
-    Value::Null
+        )*
+    };
 }
 
-/// Converts a flat serde json value into a firebase google-rpc-api inspired heavily nested and wrapped type
-/// to be consumed by the Firebase REST API.
-///
-/// This is a low level API. You probably want to use [`crate::documents`] instead.
-///
-/// This method works recursively!
-pub(crate) fn serde_value_to_firebase_value(v: &serde_json::Value) -> dto::Value {
-    if v.is_f64() {
-        return dto::Value {
-            double_value: Some(v.as_f64().unwrap()),
-            ..Default::default()
-        };
-    } else if let Some(integer_value) = v.as_i64() {
-        return dto::Value {
-            integer_value: Some(integer_value.to_string()),
-            ..Default::default()
-        };
-    } else if let Some(map_value) = v.as_object() {
-        let mut map: HashMap<String, dto::Value> = HashMap::new();
-        for (map_key, map_v) in map_value {
-            map.insert(map_key.to_owned(), serde_value_to_firebase_value(map_v));
-        }
-        return dto::Value {
-            map_value: Some(dto::MapValue { fields: Some(map) }),
-            ..Default::default()
-        };
-    } else if let Some(string_value) = v.as_str() {
-        return dto::Value {
-            string_value: Some(string_value.to_owned()),
-            ..Default::default()
-        };
-    } else if let Some(boolean_value) = v.as_bool() {
-        return dto::Value {
-            boolean_value: Some(boolean_value),
-            ..Default::default()
-        };
-    } else if let Some(array_value) = v.as_array() {
-        let mut vec: Vec<dto::Value> = Vec::new();
-        for k in array_value {
-            vec.push(serde_value_to_firebase_value(k));
-        }
-        return dto::Value {
-            array_value: Some(dto::ArrayValue { values: Some(vec) }),
-            ..Default::default()
-        };
+impl_from_for_value!(
+    &str,
+    String,
+    bool,
+    i32,
+    i64,
+    u32,
+    f64,
+    values::Timestamp,
+    values::Bytes,
+    values::GeoPoint,
+    values::DocumentReference
+);
+
+impl From<chrono::DateTime<chrono::Utc>> for dto::Value {
+    fn from(v: chrono::DateTime<chrono::Utc>) -> Self {
+        values::Timestamp(v).into()
     }
-    Default::default()
 }
 
 /// Converts a firebase google-rpc-api inspired heavily nested and wrapped response document
@@ -115,33 +59,18 @@ pub(crate) fn serde_value_to_firebase_value(v: &serde_json::Value) -> dto::Value
 ///
 /// Internals:
 ///
-/// This method uses recursion to decode the given firebase type.
+/// The document fields are deserialized directly, see [`crate::values`].
 pub fn document_to_pod<T>(document: &dto::Document) -> Result<T>
 where
     for<'de> T: Deserialize<'de>,
 {
-    // The firebase document has a field called "fields" that contain all top-level fields.
-    // We want those to be flattened to our custom data structure. To not reinvent the wheel,
-    // perform the firebase-value to serde-values conversion for all fields first and wrap those
-    // Wrapper struct with a HashMap. Use #[serde(flatten)] on that map.
-    let r = Wrapper {
-        extra: document
-            .fields
-            .as_ref()
-            .unwrap()
-            .iter()
-            .map(|(k, v)| {
-                (k.to_owned(), firebase_value_to_serde_value(v))
-            })
-            .collect(),
-    };
-
-    let v = serde_json::to_value(r)?;
-    let r: T = serde_json::from_value(v).map_err(|e| FirebaseError::Ser {
-        doc: Some(document.name.clone()),
-        ser: e,
-    })?;
-    Ok(r)
+    values::from_fields(document.fields.as_ref()).map_err(|e| match e {
+        FirebaseError::Ser { ser, .. } => FirebaseError::Ser {
+            doc: Some(document.name.clone()),
+            ser,
+        },
+        e => e,
+    })
 }
 
 /// Converts a custom data type into a firebase google-rpc-api inspired heavily nested and wrapped type
@@ -151,14 +80,13 @@ where
 ///
 /// Internals:
 ///
-/// This method uses recursion to decode the given firebase type.
+/// The custom data type is serialized directly, see [`crate::values`].
 pub fn pod_to_document<T>(pod: &T) -> Result<dto::Document>
 where
     T: Serialize,
 {
-    let v = serde_json::to_value(pod)?;
     Ok(dto::Document {
-        fields: serde_value_to_firebase_value(&v).map_value.unwrap().fields,
+        fields: Some(values::to_fields(pod)?),
         ..Default::default()
     })
 }
@@ -235,4 +163,12 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_json_value_conversion_errors() {
+        let value = dto::Value::try_from(serde_json::json!(u64::MAX));
+        assert!(matches!(value, Err(FirebaseError::Ser { .. })));
+        let value = dto::Value::try_from(serde_json::json!([1, "a"])).unwrap();
+        assert_eq!(value.array_value.unwrap().values.unwrap().len(), 2);
+    }
 }
@@ -1,6 +1,7 @@
 //! # A Firestore Auth Session token is a Javascript Web Token (JWT). This module contains JWT helper functions.
 
 use super::credentials::Credentials;
+use super::endpoints::endpoints;
 
 use serde::{Deserialize, Serialize};
 
@@ -59,12 +60,16 @@ impl JWKSet {
     }
 }
 
+#[inline]
+fn jwks_url(account_mail: &str) -> String {
+    format!("{}/service_accounts/v1/jwk/{}", endpoints().google_apis, account_mail)
+}
+
 /// Download the Google JWK Set for a given service account.
 /// The resulting set of JWKs need to be added to a credentials object
 /// for jwk verifications.
 pub fn download_google_jwks(account_mail: &str) -> Result<String, Error> {
-    let url = format!("https://www.googleapis.com/service_accounts/v1/jwk/{}", account_mail);
-    let resp = reqwest::blocking::Client::new().get(url).send()?;
+    let resp = reqwest::blocking::Client::new().get(jwks_url(account_mail)).send()?;
     Ok(resp.text()?)
 }
 
@@ -72,13 +77,7 @@ pub fn download_google_jwks(account_mail: &str) -> Result<String, Error> {
 /// The resulting set of JWKs need to be added to a credentials object
 /// for jwk verifications.
 pub async fn download_google_jwks_async(account_mail: &str) -> Result<String, Error> {
-    let resp = reqwest::Client::new()
-        .get(&format!(
-            "https://www.googleapis.com/service_accounts/v1/jwk/{}",
-            account_mail
-        ))
-        .send()
-        .await?;
+    let resp = reqwest::Client::new().get(jwks_url(account_mail)).send().await?;
     Ok(resp.text().await?)
 }
 
@@ -1,11 +1,13 @@
 pub mod credentials;
 pub mod documents;
 pub mod dto;
+pub mod endpoints;
 pub mod errors;
 pub mod firebase_rest_to_rust;
 pub mod jwt;
 pub mod sessions;
 pub mod users;
+pub mod values;
 
 #[cfg(feature = "rocket_support")]
 pub mod rocket;
@@ -13,6 +15,8 @@ pub mod rocket;
 // Forward declarations
 pub use credentials::Credentials;
 pub use jwt::JWKSet;
+pub use sessions::database::WithDatabase;
+pub use sessions::emulator::OwnerSession as EmulatorOwnerSession;
 pub use sessions::service_account::AsyncSession as AsyncServiceSession;
 pub use sessions::service_account::BlockingSession as BlockingServiceSession;
 pub use sessions::user::AsyncSession as AsyncUserSession;
@@ -30,6 +34,11 @@ use async_trait::async_trait;
 pub trait FirebaseAuthBearer {
     /// Return the project ID. This is required for the firebase REST API.
     fn project_id(&self) -> &str;
+    /// Return the Firestore database ID. Implement this to access a named database,
+    /// or wrap the session in a [`sessions::database::WithDatabase`].
+    fn database_id(&self) -> &str {
+        documents::DEFAULT_DATABASE_ID
+    }
     /// An access token. If a refresh token is known and the access token expired,
     /// the implementation should try to refresh the access token before returning.
     fn access_token(&self) -> String;
@@ -47,6 +56,11 @@ pub trait FirebaseAuthBearer {
 pub trait FirebaseAuthBearerAsync {
     /// Return the project ID. This is required for the firebase REST API.
     fn project_id(&self) -> &str;
+    /// Return the Firestore database ID. Implement this to access a named database,
+    /// or wrap the session in a [`sessions::database::WithDatabase`].
+    fn database_id(&self) -> &str {
+        documents::DEFAULT_DATABASE_ID
+    }
     /// An access token. If a refresh token is known and the access token expired,
     /// the implementation should try to refresh the access token before returning.
     async fn access_token(&mut self) -> String;
@@ -56,3 +70,43 @@ pub trait FirebaseAuthBearerAsync {
     /// The `Client` holds a connection pool internally, so it is advised that it is reused for multiple, successive connections.
     fn client_async(&self) -> &reqwest::Client;
 }
+
+impl<T: FirebaseAuthBearer + ?Sized> FirebaseAuthBearer for &T {
+    fn project_id(&self) -> &str {
+        (**self).project_id()
+    }
+    fn database_id(&self) -> &str {
+        (**self).database_id()
+    }
+    fn access_token(&self) -> String {
+        (**self).access_token()
+    }
+    fn access_token_unchecked(&self) -> String {
+        (**self).access_token_unchecked()
+    }
+    fn client(&self) -> &reqwest::blocking::Client {
+        (**self).client()
+    }
+    fn client_async(&self) -> &reqwest::Client {
+        (**self).client_async()
+    }
+}
+
+#[async_trait]
+impl<T: FirebaseAuthBearerAsync + Send + ?Sized> FirebaseAuthBearerAsync for &mut T {
+    fn project_id(&self) -> &str {
+        (**self).project_id()
+    }
+    fn database_id(&self) -> &str {
+        (**self).database_id()
+    }
+    async fn access_token(&mut self) -> String {
+        (**self).access_token().await
+    }
+    fn access_token_unchecked(&self) -> String {
+        (**self).access_token_unchecked()
+    }
+    fn client_async(&self) -> &reqwest::Client {
+        (**self).client_async()
+    }
+}
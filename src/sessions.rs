@@ -3,6 +3,7 @@
 //! A session can be either for a service-account or impersonated via a firebase auth user id.
 
 use super::credentials;
+use super::endpoints::endpoints;
 use super::errors::{extract_google_api_error, FirebaseError};
 use super::jwt::{
     create_jwt, is_expired, jwt_update_expiry_if, verify_access_token, AuthClaimsJWT, JWT_AUDIENCE_FIRESTORE,
@@ -25,14 +26,20 @@ pub mod user {
     #[inline]
     fn token_endpoint(v: &str) -> String {
         format!(
-            "https://www.googleapis.com/identitytoolkit/v3/relyingparty/verifyCustomToken?key={}",
+            "{}/identitytoolkit/v3/relyingparty/verifyCustomToken?key={}",
+            endpoints().google_apis,
             v
         )
     }
 
     #[inline]
     fn refresh_to_access_endpoint(v: &str) -> String {
-        format!("https://securetoken.googleapis.com/v1/token?key={}", v)
+        format!("{}/v1/token?key={}", endpoints().secure_token, v)
+    }
+
+    #[inline]
+    fn sign_in_with_idp_endpoint(v: &str) -> String {
+        format!("{}/v1/accounts:signInWithIdp?key={}", endpoints().identity_toolkit, v)
     }
 
     /// Default OAuth2 Providers supported by Firebase.
@@ -403,8 +410,7 @@ pub mod user {
             request_uri: String,
             with_refresh_token: bool,
         ) -> Result<BlockingSession, FirebaseError> {
-            let uri = "https://identitytoolkit.googleapis.com/v1/accounts:signInWithIdp?key=".to_owned()
-                + &credentials.api_key;
+            let uri = sign_in_with_idp_endpoint(&credentials.api_key);
 
             let post_body = format!("access_token={}&providerId={}", access_token, get_provider(provider));
             let return_idp_credential = true;
@@ -591,8 +597,7 @@ pub mod user {
             request_uri: String,
             with_refresh_token: bool,
         ) -> Result<AsyncSession, FirebaseError> {
-            let uri = "https://identitytoolkit.googleapis.com/v1/accounts:signInWithIdp?key=".to_owned()
-                + &credentials.api_key;
+            let uri = sign_in_with_idp_endpoint(&credentials.api_key);
 
             let post_body = format!("access_token={}&providerId={}", access_token, get_provider(provider));
             let return_idp_credential = true;
@@ -623,7 +628,8 @@ pub mod session_cookie {
     #[inline]
     fn identitytoolkit_url(project_id: &str) -> String {
         format!(
-            "https://identitytoolkit.googleapis.com/v1/projects/{}:createSessionCookie",
+            "{}/v1/projects/{}:createSessionCookie",
+            endpoints().identity_toolkit,
             project_id
         )
     }
@@ -943,3 +949,177 @@ pub mod service_account {
         }
     }
 }
+
+/// Find the database session wrapper defined in here
+pub mod database {
+    use super::*;
+
+    /// Wraps a session to access a named Firestore database instead of the "(default)" database.
+    ///
+    /// Wrap an owned session to use the database for all calls, or wrap a reference for a single call.
+    /// All functions of [`crate::documents`] accept the wrapper, including batches and transactions.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use firestore_db_and_auth::{documents, WithDatabase};
+    /// # use firestore_db_and_auth::{BlockingServiceSession, credentials::doctest_credentials};
+    /// # let session = BlockingServiceSession::new(doctest_credentials())?;
+    ///
+    /// // Per call
+    /// documents::delete(&WithDatabase::new(&session, "eu-tenant"), "tests/test", false)?;
+    ///
+    /// // Per session
+    /// let session = WithDatabase::new(session, "us-tenant");
+    /// documents::delete(&session, "tests/test", false)?;
+    /// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+    /// ```
+    #[derive(Clone)]
+    pub struct WithDatabase<S> {
+        /// The wrapped session
+        pub session: S,
+        database_id_: String,
+    }
+
+    impl<S> WithDatabase<S> {
+        /// Wraps the session, which can be a reference, to use the database with the given id
+        pub fn new(session: S, database_id: &str) -> Self {
+            WithDatabase {
+                session,
+                database_id_: database_id.to_owned(),
+            }
+        }
+    }
+
+    impl<S: super::FirebaseAuthBearer> super::FirebaseAuthBearer for WithDatabase<S> {
+        fn project_id(&self) -> &str {
+            self.session.project_id()
+        }
+
+        fn database_id(&self) -> &str {
+            &self.database_id_
+        }
+
+        fn access_token(&self) -> String {
+            self.session.access_token()
+        }
+
+        fn access_token_unchecked(&self) -> String {
+            self.session.access_token_unchecked()
+        }
+
+        fn client(&self) -> &reqwest::blocking::Client {
+            self.session.client()
+        }
+
+        fn client_async(&self) -> &reqwest::Client {
+            self.session.client_async()
+        }
+    }
+
+    #[async_trait]
+    impl<S: crate::FirebaseAuthBearerAsync + Send> crate::FirebaseAuthBearerAsync for WithDatabase<S> {
+        fn project_id(&self) -> &str {
+            self.session.project_id()
+        }
+
+        fn database_id(&self) -> &str {
+            &self.database_id_
+        }
+
+        async fn access_token(&mut self) -> String {
+            self.session.access_token().await
+        }
+
+        fn access_token_unchecked(&self) -> String {
+            self.session.access_token_unchecked()
+        }
+
+        fn client_async(&self) -> &reqwest::Client {
+            self.session.client_async()
+        }
+    }
+}
+
+/// Find the emulator session defined in here
+pub mod emulator {
+    use super::*;
+
+    /// The bearer token that the Firestore emulator accepts as an administrator, bypassing all security rules
+    pub static OWNER_TOKEN: &str = "owner";
+
+    /// A session for the Firestore emulator, see [`crate::endpoints`].
+    ///
+    /// It does not need any credentials and uses the "owner" token of the emulator,
+    /// which bypasses all security rules. It implements the blocking and the async authentication trait.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use firestore_db_and_auth::{documents, EmulatorOwnerSession};
+    ///
+    /// // Requests are sent to the emulator, if FIRESTORE_EMULATOR_HOST is set, for example to "localhost:8080"
+    /// let session = EmulatorOwnerSession::new("demo-project");
+    /// documents::delete(&session, "tests/test", false)?;
+    /// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+    /// ```
+    #[derive(Clone)]
+    pub struct OwnerSession {
+        project_id_: String,
+        /// The http client. Replace or modify the client if you have special demands like proxy support
+        pub client: reqwest::blocking::Client,
+        /// The http client for async operations. Replace or modify the client if you have special demands like proxy support
+        pub client_async: reqwest::Client,
+    }
+
+    impl OwnerSession {
+        /// Creates an emulator session for the given project id.
+        /// The emulator accepts any project id. Project ids starting with "demo-" are never sent to production.
+        pub fn new(project_id: &str) -> Self {
+            OwnerSession {
+                project_id_: project_id.to_owned(),
+                client: reqwest::blocking::Client::new(),
+                client_async: reqwest::Client::new(),
+            }
+        }
+    }
+
+    impl super::FirebaseAuthBearer for OwnerSession {
+        fn project_id(&self) -> &str {
+            &self.project_id_
+        }
+
+        fn access_token(&self) -> String {
+            OWNER_TOKEN.to_owned()
+        }
+
+        fn access_token_unchecked(&self) -> String {
+            OWNER_TOKEN.to_owned()
+        }
+
+        fn client(&self) -> &reqwest::blocking::Client {
+            &self.client
+        }
+
+        fn client_async(&self) -> &reqwest::Client {
+            &self.client_async
+        }
+    }
+
+    #[async_trait]
+    impl crate::FirebaseAuthBearerAsync for OwnerSession {
+        fn project_id(&self) -> &str {
+            &self.project_id_
+        }
+
+        async fn access_token(&mut self) -> String {
+            OWNER_TOKEN.to_owned()
+        }
+
+        fn access_token_unchecked(&self) -> String {
+            OWNER_TOKEN.to_owned()
+        }
+
+        fn client_async(&self) -> &reqwest::Client {
+            &self.client_async
+        }
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Retrieve firebase user information
 
+use super::endpoints::endpoints;
 use super::errors::{extract_google_api_error, Result};
 
 use super::sessions::{service_account, user};
@@ -59,7 +60,7 @@ struct UserRequest {
 
 #[inline]
 fn firebase_auth_url(v: &str, v2: &str) -> String {
-    format!("https://identitytoolkit.googleapis.com/v1/accounts:{}?key={}", v, v2)
+    format!("{}/v1/accounts:{}?key={}", endpoints().identity_toolkit, v, v2)
 }
 
 /// Retrieve information about the firebase auth user associated with the given user session
@@ -210,10 +211,7 @@ async fn async_update_user(
     email: Option<&str>,
     password: Option<&str>,
 ) -> Result<Option<UpdateUser>> {
-    let url = format!(
-        "https://identitytoolkit.googleapis.com/v1/accounts:update?key={}",
-        session.api_key,
-    );
+    let url = firebase_auth_url("update", &session.api_key);
 
     let resp = session
         .client_async()
@@ -270,10 +268,7 @@ async fn async_send_oob_code(
     request_type: &str,
     email: Option<&str>,
 ) -> Result<Option<SendOobCode>> {
-    let url = format!(
-        "https://identitytoolkit.googleapis.com/v1/accounts:sendOobCode?key={}",
-        session.api_key,
-    );
+    let url = firebase_auth_url("sendOobCode", &session.api_key);
 
     let resp = session
         .client_async()
@@ -0,0 +1,323 @@
+use super::Error;
+use crate::dto;
+use serde::de::{self, value::BorrowedStrDeserializer, IntoDeserializer, Visitor};
+use std::collections::{hash_map, HashMap};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// A serde deserializer that converts a Firestore value into Rust types, see [`super::from_value`]
+pub struct Deserializer<'de> {
+    value: &'de dto::Value,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer for the given value
+    pub fn new(value: &'de dto::Value) -> Self {
+        Deserializer { value }
+    }
+}
+
+/// Returns true if no value type is set. Such values are treated as null.
+fn is_empty(v: &dto::Value) -> bool {
+    v.null_value.is_none()
+        && v.boolean_value.is_none()
+        && v.integer_value.is_none()
+        && v.double_value.is_none()
+        && v.timestamp_value.is_none()
+        && v.string_value.is_none()
+        && v.bytes_value.is_none()
+        && v.reference_value.is_none()
+        && v.geo_point_value.is_none()
+        && v.array_value.is_none()
+        && v.map_value.is_none()
+}
+
+fn parse_integer(v: &str) -> Result<i64> {
+    v.parse()
+        .map_err(|_| Error::new(format!("Invalid integer value '{}'", v)))
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let v = self.value;
+        if let Some(boolean_value) = v.boolean_value {
+            visitor.visit_bool(boolean_value)
+        } else if let Some(integer_value) = v.integer_value.as_deref() {
+            visitor.visit_i64(parse_integer(integer_value)?)
+        } else if let Some(double_value) = v.double_value {
+            visitor.visit_f64(double_value)
+        } else if let Some(string_value) = v.string_value.as_deref() {
+            visitor.visit_borrowed_str(string_value)
+        } else if let Some(timestamp_value) = v.timestamp_value.as_deref() {
+            visitor.visit_borrowed_str(timestamp_value)
+        } else if let Some(bytes_value) = v.bytes_value.as_deref() {
+            visitor.visit_borrowed_str(bytes_value)
+        } else if let Some(reference_value) = v.reference_value.as_deref() {
+            visitor.visit_borrowed_str(reference_value)
+        } else if let Some(geo_point_value) = v.geo_point_value.as_ref() {
+            let fields = vec![
+                ("latitude", geo_point_value.latitude.unwrap_or_default()),
+                ("longitude", geo_point_value.longitude.unwrap_or_default()),
+            ];
+            visitor.visit_map(de::value::MapDeserializer::new(fields.into_iter()))
+        } else if let Some(array_value) = v.array_value.as_ref() {
+            let values = array_value.values.as_deref().unwrap_or_default();
+            let mut seq = SeqAccess {
+                values: values.iter(),
+                index: 0,
+            };
+            let result = visitor.visit_seq(&mut seq)?;
+            seq.end()?;
+            Ok(result)
+        } else if let Some(map_value) = v.map_value.as_ref() {
+            visitor.visit_map(MapAccess::new(map_value.fields.as_ref()))
+        } else {
+            visitor.visit_unit()
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.value.null_value.is_some() || is_empty(self.value) {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value.bytes_value.as_deref() {
+            Some(bytes_value) => {
+                let bytes =
+                    base64::decode(bytes_value).map_err(|e| Error::new(format!("Invalid bytes value: {}", e)))?;
+                visitor.visit_byte_buf(bytes)
+            }
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if let Some(variant) = self.value.string_value.as_deref() {
+            return visitor.visit_enum(BorrowedStrDeserializer::new(variant));
+        }
+        let fields = self.value.map_value.as_ref().and_then(|m| m.fields.as_ref());
+        match fields.map(|fields| (fields.len(), fields.iter().next())) {
+            Some((1, Some((variant, value)))) => visitor.visit_enum(EnumAccess { variant, value }),
+            _ => Err(Error::new(
+                "An enum must be a string or a map with a single key, the variant",
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+/// Visits the values of an array value
+struct SeqAccess<'de> {
+    values: std::slice::Iter<'de, dto::Value>,
+    index: usize,
+}
+
+impl<'de> SeqAccess<'de> {
+    fn end(self) -> Result<()> {
+        match self.values.len() {
+            0 => Ok(()),
+            remaining => Err(Error::new(format!(
+                "Invalid length {}, {} elements are left over",
+                self.index + remaining,
+                remaining
+            ))),
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for &mut SeqAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.values.next() {
+            Some(value) => {
+                let index = self.index;
+                self.index += 1;
+                seed.deserialize(Deserializer::new(value))
+                    .map(Some)
+                    .map_err(|e| e.at_index(index))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// Visits the fields of a map value or of a document
+pub(crate) struct MapAccess<'de> {
+    fields: Option<hash_map::Iter<'de, String, dto::Value>>,
+    value: Option<(&'de str, &'de dto::Value)>,
+}
+
+impl<'de> MapAccess<'de> {
+    pub(crate) fn new(fields: Option<&'de HashMap<String, dto::Value>>) -> Self {
+        MapAccess {
+            fields: fields.map(|fields| fields.iter()),
+            value: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.fields.as_mut().and_then(|fields| fields.next()) {
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(MapKeyDeserializer { key }).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let (key, value) = self
+            .value
+            .take()
+            .ok_or_else(|| Error::new("next_value_seed called before next_key_seed"))?;
+        seed.deserialize(Deserializer::new(value)).map_err(|e| e.at_field(key))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.fields.as_ref().map(|fields| fields.len())
+    }
+}
+
+/// Deserializes a map key. Keys are always strings, but may hold numbers or booleans.
+struct MapKeyDeserializer<'de> {
+    key: &'de str,
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                match self.key.parse() {
+                    Ok(key) => visitor.$visit(key),
+                    Err(_) => Err(Error::new(format!("Invalid map key '{}'", self.key)).at_field(self.key)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for MapKeyDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_borrowed_str(self.key)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(BorrowedStrDeserializer::new(self.key))
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// An enum variant with content, a map with a single key
+struct EnumAccess<'de> {
+    variant: &'de str,
+    value: &'de dto::Value,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess<'de> {
+    type Error = Error;
+    type Variant = VariantAccess<'de>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, VariantAccess<'de>)> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((
+            variant,
+            VariantAccess {
+                variant: self.variant,
+                value: self.value,
+            },
+        ))
+    }
+}
+
+struct VariantAccess<'de> {
+    variant: &'de str,
+    value: &'de dto::Value,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(Deserializer::new(self.value))
+            .map_err(|e| e.at_field(self.variant))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(Deserializer::new(self.value), visitor).map_err(|e| e.at_field(self.variant))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(Deserializer::new(self.value), visitor).map_err(|e| e.at_field(self.variant))
+    }
+}
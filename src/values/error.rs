@@ -0,0 +1,84 @@
+use serde::{de, ser};
+use std::fmt;
+
+/// A path segment of a value error, innermost first
+#[derive(Clone, Debug, PartialEq)]
+enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+/// The error of the [`super::Serializer`] and [`super::Deserializer`].
+///
+/// Contains the path to the failed value, for example "cars[2].owner.name".
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    message: String,
+    path: Vec<PathSegment>,
+}
+
+impl Error {
+    /// The reason, without the path
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The path to the failed value, like "cars[2].owner.name". Empty for the root value.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in self.path.iter().rev() {
+            match segment {
+                PathSegment::Field(name) if path.is_empty() => path.push_str(name),
+                PathSegment::Field(name) => {
+                    path.push('.');
+                    path.push_str(name);
+                }
+                PathSegment::Index(index) => path.push_str(&format!("[{}]", index)),
+            }
+        }
+        path
+    }
+
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+            path: Vec::new(),
+        }
+    }
+
+    /// Prepends a field name to the path, while the error bubbles up
+    pub(crate) fn at_field(mut self, name: &str) -> Self {
+        self.path.push(PathSegment::Field(name.to_owned()));
+        self
+    }
+
+    /// Prepends an array index to the path, while the error bubbles up
+    pub(crate) fn at_index(mut self, index: usize) -> Self {
+        self.path.push(PathSegment::Index(index));
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.path(), self.message)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::new(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::new(msg.to_string())
+    }
+}
@@ -0,0 +1,344 @@
+//! # Firestore Values
+//!
+//! A serde [`Serializer`] and [`Deserializer`] that convert between Rust types and the
+//! [`dto::Value`] type of the Firestore REST API.
+//!
+//! The conversion is direct, there is no intermediate json value.
+//! Errors contain the path to the failed value, for example "cars[2].owner.name".
+//! See [`Timestamp`], [`Bytes`], [`GeoPoint`] and [`DocumentReference`] for fields that should be
+//! stored as the matching Firestore value types instead of strings, arrays or maps.
+//!
+//! ## Numbers
+//!
+//! The Rust type decides between Firestore integers and doubles, not the value:
+//! * Integer types are stored as `integerValue`. Firestore integers are signed 64 bit integers,
+//!   a `u64` above `i64::MAX` is an error. See [`saturating`] to store `i64::MAX` instead.
+//! * `f32` and `f64` are stored as `doubleValue`, also for whole numbers like `1.0`.
+//!   NaN and infinity are preserved.
+//! * Integer fields can not be read from a `doubleValue`. Float fields can be read from
+//!   an `integerValue`.
+//!
+//! ## Null
+//!
+//! `None` and `()` are stored as `nullValue`. Use `#[serde(skip_serializing_if = "Option::is_none")]`
+//! on a field or [`crate::documents::WriteOptions::skip_nulls`] for a whole document to not write them.
+//! Reading a `nullValue` or an absent field into an `Option` returns `None`,
+//! see [`Nullable`] to tell them apart.
+//!
+//! This is a low level API. You probably want to use [`crate::documents`] instead.
+//!
+//! Example:
+//! ```
+//! use firestore_db_and_auth::values;
+//! # use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Car { brand: String, year: i32 }
+//!
+//! let car = Car { brand: "Tesla".to_owned(), year: 2016 };
+//! let value = values::to_value(&car)?;
+//! assert_eq!(values::from_value::<Car>(&value)?, car);
+//! # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+//! ```
+
+use super::dto;
+use super::errors::Result;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+mod de;
+mod error;
+mod nullable;
+pub mod saturating;
+mod ser;
+pub mod timestamp;
+mod types;
+
+pub use de::Deserializer;
+pub use error::Error;
+pub use nullable::Nullable;
+pub use ser::Serializer;
+pub use timestamp::Timestamp;
+pub use types::{Bytes, DocumentReference, GeoPoint};
+
+/// Converts a Rust type into a Firestore value
+pub fn to_value<T: ?Sized + Serialize>(value: &T) -> Result<dto::Value> {
+    Ok(value.serialize(Serializer)?)
+}
+
+/// Converts a Firestore value into a Rust type
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de dto::Value) -> Result<T> {
+    Ok(T::deserialize(Deserializer::new(value))?)
+}
+
+/// Converts a Rust type, usually a struct or a map, into the fields of a document
+pub fn to_fields<T: ?Sized + Serialize>(value: &T) -> Result<HashMap<String, dto::Value>> {
+    match value.serialize(Serializer)?.map_value {
+        Some(map_value) => Ok(map_value.fields.unwrap_or_default()),
+        None => Err(Error::new("A document must be a struct or a map").into()),
+    }
+}
+
+/// Converts the fields of a document into a Rust type, usually a struct or a map
+pub fn from_fields<'de, T: Deserialize<'de>>(fields: Option<&'de HashMap<String, dto::Value>>) -> Result<T> {
+    let map = serde::de::value::MapAccessDeserializer::new(de::MapAccess::new(fields));
+    Ok(T::deserialize(map)?)
+}
+
+/// Removes all null fields, also of nested maps. Nested maps that only contained nulls are removed as well.
+/// Nulls in arrays are kept.
+pub fn remove_nulls(fields: &mut HashMap<String, dto::Value>) {
+    fields.retain(|_, value| {
+        if value.null_value.is_some() {
+            return false;
+        }
+        match value.map_value.as_mut().and_then(|m| m.fields.as_mut()) {
+            Some(nested) if !nested.is_empty() => {
+                remove_nulls(nested);
+                !nested.is_empty()
+            }
+            _ => true,
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::FirebaseError;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Fuel {
+        Electric,
+        Gas { octane: u8 },
+        Hybrid(String, f64),
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Car {
+        brand: String,
+        year: i32,
+        price: f64,
+        sold: bool,
+        owner: Option<String>,
+        tags: Vec<String>,
+        fuel: Vec<Fuel>,
+        extras: HashMap<u32, String>,
+    }
+
+    fn car() -> Car {
+        Car {
+            brand: "Tesla".to_owned(),
+            year: 2016,
+            price: 12.5,
+            sold: true,
+            owner: None,
+            tags: vec!["fast".to_owned()],
+            fuel: vec![
+                Fuel::Electric,
+                Fuel::Gas { octane: 95 },
+                Fuel::Hybrid("plug-in".to_owned(), 0.5),
+            ],
+            extras: vec![(1, "roof".to_owned())].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn it_converts_values_round_trip() -> Result<()> {
+        let fields = to_fields(&car())?;
+        assert_eq!(fields["year"].integer_value.as_deref(), Some("2016"));
+        assert_eq!(fields["price"].double_value, Some(12.5));
+        assert_eq!(
+            fields["fuel"].array_value.as_ref().unwrap().values.as_ref().unwrap()[0]
+                .string_value
+                .as_deref(),
+            Some("Electric")
+        );
+        assert_eq!(from_fields::<Car>(Some(&fields))?, car());
+
+        // Borrowed strings
+        let value = to_value("abc")?;
+        assert_eq!(from_value::<&str>(&value)?, "abc");
+        Ok(())
+    }
+
+    #[test]
+    fn it_converts_timestamps() -> Result<()> {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Event {
+            #[serde(with = "timestamp")]
+            created: DateTime<Utc>,
+            #[serde(with = "timestamp::option")]
+            deleted: Option<DateTime<Utc>>,
+            updated: Timestamp,
+        }
+
+        let created = Utc.timestamp_opt(1_600_000_000, 123_456_789).unwrap();
+        let event = Event {
+            created,
+            deleted: Some(created),
+            updated: created.into(),
+        };
+        let fields = to_fields(&event)?;
+        assert_eq!(
+            fields["created"].timestamp_value.as_deref(),
+            Some("2020-09-13T12:26:40.123456789Z")
+        );
+        assert_eq!(fields["deleted"].timestamp_value, fields["created"].timestamp_value);
+        assert_eq!(fields["updated"].timestamp_value, fields["created"].timestamp_value);
+        assert!(fields["created"].string_value.is_none());
+        assert_eq!(from_fields::<Event>(Some(&fields))?, event);
+
+        // Filter values and plain chrono fields read from a timestampValue
+        let value: dto::Value = created.into();
+        assert_eq!(value.timestamp_value, fields["created"].timestamp_value);
+        assert_eq!(from_value::<DateTime<Utc>>(&value)?, created);
+        Ok(())
+    }
+
+    #[test]
+    fn it_converts_bytes_geo_points_and_references() -> Result<()> {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Place {
+            photo: Bytes,
+            location: GeoPoint,
+            owner: DocumentReference,
+        }
+
+        let place = Place {
+            photo: vec![0, 1, 255].into(),
+            location: GeoPoint::new(52.5, 13.4),
+            owner: DocumentReference::new("projects/p/databases/(default)/documents/users/alice"),
+        };
+        let fields = to_fields(&place)?;
+        assert_eq!(fields["photo"].bytes_value.as_deref(), Some("AAH/"));
+        let location = fields["location"].geo_point_value.as_ref().unwrap();
+        assert_eq!((location.latitude, location.longitude), (Some(52.5), Some(13.4)));
+        assert!(fields["location"].map_value.is_none());
+        assert_eq!(fields["owner"].reference_value.as_deref(), Some(place.owner.name()));
+        assert_eq!(from_fields::<Place>(Some(&fields))?, place);
+        assert_eq!(place.owner.document_ref()?.path(), "users/alice");
+
+        // Other formats use plain arrays, maps and strings
+        let json = serde_json::to_value(&place)?;
+        assert_eq!(json["location"]["latitude"], 52.5);
+        assert_eq!(serde_json::from_value::<Place>(json)?, place);
+        Ok(())
+    }
+
+    #[test]
+    fn it_keeps_integers_and_doubles_apart() -> Result<()> {
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Numbers {
+            whole: f64,
+            nan: f64,
+            infinity: f32,
+            #[serde(with = "saturating")]
+            huge: u64,
+            #[serde(with = "saturating")]
+            negative: u64,
+        }
+
+        let mut fields = to_fields(&Numbers {
+            whole: 1.0,
+            nan: f64::NAN,
+            infinity: f32::INFINITY,
+            huge: u64::MAX,
+            negative: 0,
+        })?;
+        assert_eq!(fields["whole"].double_value, Some(1.0));
+        assert!(fields["whole"].integer_value.is_none());
+        assert_eq!(fields["huge"].integer_value.as_deref(), Some("9223372036854775807"));
+        fields.insert("negative".to_owned(), to_value(&-5)?);
+
+        let numbers: Numbers = from_fields(Some(&fields))?;
+        assert!(numbers.nan.is_nan());
+        assert_eq!(numbers.infinity, f32::INFINITY);
+        assert_eq!(numbers.huge, i64::MAX as u64);
+        assert_eq!(numbers.negative, 0);
+
+        assert_eq!(from_value::<f64>(&to_value(&3)?)?, 3.0);
+        assert!(from_value::<i64>(&to_value(&3.0)?).is_err());
+        assert!(to_value(&u64::MAX).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_writes_and_removes_nulls() -> Result<()> {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Profile {
+            nickname: Option<String>,
+            #[serde(default, skip_serializing_if = "Nullable::is_missing")]
+            deleted_at: Nullable<Timestamp>,
+            scores: Vec<Option<i32>>,
+        }
+
+        let mut profile = Profile {
+            nickname: None,
+            deleted_at: Nullable::Missing,
+            scores: vec![None],
+        };
+        let mut fields = to_fields(&serde_json::json!({ "profile": profile }))?;
+        assert!(
+            fields["profile"].map_value.as_ref().unwrap().fields.as_ref().unwrap()["nickname"]
+                .null_value
+                .is_some()
+        );
+        assert_eq!(
+            from_fields::<HashMap<String, Profile>>(Some(&fields))?["profile"],
+            profile
+        );
+
+        remove_nulls(&mut fields);
+        let nested = fields["profile"].map_value.as_ref().unwrap().fields.as_ref().unwrap();
+        assert!(!nested.contains_key("nickname"));
+        assert_eq!(
+            nested["scores"]
+                .array_value
+                .as_ref()
+                .unwrap()
+                .values
+                .as_ref()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        profile.deleted_at = Nullable::Null;
+        let fields = to_fields(&profile)?;
+        assert!(fields["deleted_at"].null_value.is_some());
+        assert_eq!(from_fields::<Profile>(Some(&fields))?.deleted_at, Nullable::Null);
+        Ok(())
+    }
+
+    #[test]
+    fn it_reports_error_paths() {
+        let mut fields = to_fields(&car()).unwrap();
+        let fuel = fields.get_mut("fuel").unwrap().array_value.as_mut().unwrap();
+        fuel.values.as_mut().unwrap()[1] = to_value(&Fuel::Gas { octane: 0 }).unwrap();
+        fuel.values.as_mut().unwrap()[1]
+            .map_value
+            .as_mut()
+            .unwrap()
+            .fields
+            .as_mut()
+            .unwrap()
+            .insert(
+                "Gas".to_owned(),
+                to_value(&serde_json::json!({"octane": "high"})).unwrap(),
+            );
+
+        match from_fields::<Car>(Some(&fields)) {
+            Err(FirebaseError::Ser { ser, .. }) => assert!(ser.to_string().starts_with("fuel[1].Gas.octane: ")),
+            _ => panic!("Expected a deserialization error"),
+        }
+
+        let error = ser::Serializer;
+        let error = serde::Serialize::serialize(&vec![u64::MAX], error).unwrap_err();
+        assert_eq!(error.path(), "[0]");
+        assert!(to_fields(&1).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A document field that tells an absent field apart from a `null` field.
+///
+/// An `Option` reads both as `None`. Use `Nullable` with `#[serde(default)]`, so that absent fields
+/// are [`Nullable::Missing`]. Add `skip_serializing_if = "Nullable::is_missing"` to not write
+/// missing fields, otherwise they are written as null.
+///
+/// Example:
+/// ```
+/// use firestore_db_and_auth::{dto, values::{self, Nullable}};
+/// # use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct User {
+///     #[serde(default, skip_serializing_if = "Nullable::is_missing")]
+///     deleted_at: Nullable<String>,
+/// }
+///
+/// let absent: User = values::from_fields(Some(&Default::default()))?;
+/// assert_eq!(absent.deleted_at, Nullable::Missing);
+///
+/// let fields = vec![("deleted_at".to_owned(), dto::Value::null())].into_iter().collect();
+/// let null: User = values::from_fields(Some(&fields))?;
+/// assert_eq!(null.deleted_at, Nullable::Null);
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Nullable<T> {
+    /// The field does not exist
+    #[default]
+    Missing,
+    /// The field exists and is `null`
+    Null,
+    /// The field exists and has a value
+    Value(T),
+}
+
+impl<T> Nullable<T> {
+    /// True if the field does not exist
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Nullable::Missing)
+    }
+
+    /// True if the field exists and is `null`
+    pub fn is_null(&self) -> bool {
+        matches!(self, Nullable::Null)
+    }
+
+    /// The value, `None` for missing and null fields
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Nullable::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Converts into an `Option`, missing and null fields are `None`
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Nullable::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Nullable<T> {
+    fn from(v: Option<T>) -> Self {
+        match v {
+            Some(v) => Nullable::Value(v),
+            None => Nullable::Null,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Nullable<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Nullable::Value(v) => serializer.serialize_some(v),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Nullable<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::deserialize(deserializer).map(Into::into)
+    }
+}
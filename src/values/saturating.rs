@@ -0,0 +1,53 @@
+//! # Saturating unsigned integers
+//!
+//! Firestore integers are signed 64 bit integers. A `u64` above `i64::MAX` is an error by default.
+//! Annotate a field with `#[serde(with = "firestore_db_and_auth::values::saturating")]` to store
+//! such values as `i64::MAX` instead. Negative integers, written by other clients, are read as 0.
+//!
+//! ```
+//! use firestore_db_and_auth::values;
+//! # use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Counter {
+//!     #[serde(with = "firestore_db_and_auth::values::saturating")]
+//!     hits: u64,
+//! }
+//!
+//! let fields = values::to_fields(&Counter { hits: u64::MAX })?;
+//! assert_eq!(fields["hits"].integer_value.as_deref(), Some("9223372036854775807"));
+//! # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+//! ```
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Serializes a `u64`, values above `i64::MAX` are stored as `i64::MAX`
+pub fn serialize<S: Serializer>(v: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i64(i64::try_from(*v).unwrap_or(i64::MAX))
+}
+
+struct SaturatingVisitor;
+
+impl<'de> Visitor<'de> for SaturatingVisitor {
+    type Value = u64;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an integer")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u64, E> {
+        Ok(u64::try_from(v).unwrap_or(0))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u64, E> {
+        Ok(v)
+    }
+}
+
+/// Deserializes a `u64`, negative values are read as 0
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    deserializer.deserialize_i64(SaturatingVisitor)
+}
@@ -0,0 +1,520 @@
+use super::timestamp::TIMESTAMP_TOKEN;
+use super::types::{GEO_POINT_TOKEN, REFERENCE_TOKEN};
+use super::Error;
+use crate::dto;
+use serde::ser::{self, Impossible, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// A serde serializer that converts Rust types into Firestore values, see [`super::to_value`]
+pub struct Serializer;
+
+fn integer_value(v: i64) -> dto::Value {
+    dto::Value {
+        integer_value: Some(v.to_string()),
+        ..Default::default()
+    }
+}
+
+fn string_value(v: String) -> dto::Value {
+    dto::Value {
+        string_value: Some(v),
+        ..Default::default()
+    }
+}
+
+fn array_value(values: Vec<dto::Value>) -> dto::Value {
+    dto::Value {
+        array_value: Some(dto::ArrayValue { values: Some(values) }),
+        ..Default::default()
+    }
+}
+
+fn map_value(fields: HashMap<String, dto::Value>) -> dto::Value {
+    dto::Value {
+        map_value: Some(dto::MapValue { fields: Some(fields) }),
+        ..Default::default()
+    }
+}
+
+/// A map with one entry, the enum variant
+fn variant_value(variant: &str, value: dto::Value) -> dto::Value {
+    let mut fields = HashMap::with_capacity(1);
+    fields.insert(variant.to_owned(), value);
+    map_value(fields)
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<dto::Value> {
+        Ok(dto::Value {
+            boolean_value: Some(v),
+            ..Default::default()
+        })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<dto::Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<dto::Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<dto::Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<dto::Value> {
+        Ok(integer_value(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<dto::Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<dto::Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<dto::Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<dto::Value> {
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => Err(Error::new(format!("{} does not fit into a 64 bit signed integer", v))),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<dto::Value> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<dto::Value> {
+        Ok(dto::Value {
+            double_value: Some(v),
+            ..Default::default()
+        })
+    }
+
+    fn serialize_char(self, v: char) -> Result<dto::Value> {
+        Ok(string_value(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<dto::Value> {
+        Ok(string_value(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<dto::Value> {
+        Ok(dto::Value {
+            bytes_value: Some(base64::encode(v)),
+            ..Default::default()
+        })
+    }
+
+    fn serialize_none(self) -> Result<dto::Value> {
+        Ok(dto::Value::null())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<dto::Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<dto::Value> {
+        Ok(dto::Value::null())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<dto::Value> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<dto::Value> {
+        Ok(string_value(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, name: &'static str, value: &T) -> Result<dto::Value> {
+        let mut v = value.serialize(self)?;
+        match name {
+            TIMESTAMP_TOKEN => v.timestamp_value = v.string_value.take(),
+            REFERENCE_TOKEN => v.reference_value = v.string_value.take(),
+            GEO_POINT_TOKEN => {
+                let mut fields = v.map_value.take().and_then(|m| m.fields).unwrap_or_default();
+                let mut coordinate = |name| fields.remove(name).and_then(|v: dto::Value| v.double_value);
+                v.geo_point_value = Some(dto::LatLng {
+                    latitude: coordinate("latitude"),
+                    longitude: coordinate("longitude"),
+                });
+            }
+            _ => {}
+        }
+        Ok(v)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<dto::Value> {
+        let value = value.serialize(Serializer).map_err(|e| e.at_field(variant))?;
+        Ok(variant_value(variant, value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec> {
+        Ok(SerializeVec {
+            values: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeVec> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant> {
+        Ok(SerializeTupleVariant {
+            variant,
+            values: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap> {
+        Ok(SerializeMap {
+            fields: HashMap::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant> {
+        Ok(SerializeStructVariant {
+            variant,
+            fields: HashMap::with_capacity(len),
+        })
+    }
+}
+
+/// Serializes sequences and tuples into an array value
+pub struct SerializeVec {
+    values: Vec<dto::Value>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let index = self.values.len();
+        self.values
+            .push(value.serialize(Serializer).map_err(|e| e.at_index(index))?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<dto::Value> {
+        Ok(array_value(self.values))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<dto::Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<dto::Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializes tuple variants into a map with the variant as key and an array value
+pub struct SerializeTupleVariant {
+    variant: &'static str,
+    values: Vec<dto::Value>,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let index = self.values.len();
+        let variant = self.variant;
+        self.values.push(
+            value
+                .serialize(Serializer)
+                .map_err(|e| e.at_index(index).at_field(variant))?,
+        );
+        Ok(())
+    }
+
+    fn end(self) -> Result<dto::Value> {
+        Ok(variant_value(self.variant, array_value(self.values)))
+    }
+}
+
+/// Serializes maps and structs into a map value
+pub struct SerializeMap {
+    fields: HashMap<String, dto::Value>,
+    next_key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.next_key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| Error::new("serialize_value called before serialize_key"))?;
+        let value = value.serialize(Serializer).map_err(|e| e.at_field(&key))?;
+        self.fields.insert(key, value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<dto::Value> {
+        Ok(map_value(self.fields))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        let value = value.serialize(Serializer).map_err(|e| e.at_field(key))?;
+        self.fields.insert(key.to_owned(), value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<dto::Value> {
+        Ok(map_value(self.fields))
+    }
+}
+
+/// Serializes struct variants into a map with the variant as key and a map value
+pub struct SerializeStructVariant {
+    variant: &'static str,
+    fields: HashMap<String, dto::Value>,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = dto::Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        let variant = self.variant;
+        let value = value
+            .serialize(Serializer)
+            .map_err(|e| e.at_field(key).at_field(variant))?;
+        self.fields.insert(key.to_owned(), value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<dto::Value> {
+        Ok(variant_value(self.variant, map_value(self.fields)))
+    }
+}
+
+/// Firestore map keys are strings. Like in json, integer and char keys are converted into strings.
+struct MapKeySerializer;
+
+fn key_must_be_a_string() -> Error {
+    Error::new("Map keys must be strings")
+}
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<String> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_must_be_a_string())
+    }
+}
@@ -0,0 +1,124 @@
+//! # Timestamps
+//!
+//! Firestore has a dedicated timestamp type. A `chrono::DateTime<Utc>` is serialized as a plain
+//! string by default and can therefore not be range-queried against timestamps that are written
+//! by other clients. Use the [`Timestamp`] wrapper or annotate fields with this module to store them
+//! as real `timestampValue`s:
+//!
+//! ```
+//! use firestore_db_and_auth::values::{self, Timestamp};
+//! use chrono::{DateTime, Utc};
+//! # use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "firestore_db_and_auth::values::timestamp")]
+//!     created: DateTime<Utc>,
+//!     #[serde(with = "firestore_db_and_auth::values::timestamp::option")]
+//!     deleted: Option<DateTime<Utc>>,
+//!     updated: Timestamp,
+//! }
+//!
+//! let event = Event { created: Utc::now(), deleted: None, updated: Utc::now().into() };
+//! let fields = values::to_fields(&event)?;
+//! assert!(fields["created"].timestamp_value.is_some());
+//! assert!(fields["updated"].timestamp_value.is_some());
+//! # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+//! ```
+//!
+//! With other serde formats, for example json, timestamps are RFC 3339 strings.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// The newtype struct name that the [`super::Serializer`] recognises to write a `timestampValue`
+pub(crate) const TIMESTAMP_TOKEN: &str = "$firestore_db_and_auth::Timestamp";
+
+/// A point in time that is stored as Firestore `timestampValue`, with nanosecond precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub DateTime<Utc>);
+
+impl Timestamp {
+    /// The current time
+    pub fn now() -> Self {
+        Timestamp(Utc::now())
+    }
+
+    /// The RFC 3339 representation that is used by the Firestore REST API
+    pub fn to_rfc3339(&self) -> String {
+        self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(v: DateTime<Utc>) -> Self {
+        Timestamp(v)
+    }
+}
+
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(v: Timestamp) -> Self {
+        v.0
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(TIMESTAMP_TOKEN, &self.to_rfc3339())
+    }
+}
+
+struct TimestampVisitor;
+
+impl<'de> Visitor<'de> for TimestampVisitor {
+    type Value = Timestamp;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an RFC 3339 timestamp")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Timestamp, E> {
+        DateTime::parse_from_rfc3339(v)
+            .map(|v| Timestamp(v.with_timezone(&Utc)))
+            .map_err(|e| E::custom(format!("Invalid timestamp '{}': {}", v, e)))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Timestamp, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(TIMESTAMP_TOKEN, TimestampVisitor)
+    }
+}
+
+/// Serializes a `DateTime<Utc>` as [`Timestamp`]. Use with `#[serde(with = "...")]`.
+pub fn serialize<S: Serializer>(v: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    Timestamp(*v).serialize(serializer)
+}
+
+/// Deserializes a `DateTime<Utc>` from a [`Timestamp`]. Use with `#[serde(with = "...")]`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    Timestamp::deserialize(deserializer).map(|v| v.0)
+}
+
+/// Like the parent module, but for `Option<DateTime<Utc>>` fields
+pub mod option {
+    use super::Timestamp;
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes an optional `DateTime<Utc>` as [`Timestamp`]
+    pub fn serialize<S: Serializer>(v: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+        v.map(Timestamp).serialize(serializer)
+    }
+
+    /// Deserializes an optional `DateTime<Utc>` from a [`Timestamp`]
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Ok(Option::<Timestamp>::deserialize(deserializer)?.map(|v| v.0))
+    }
+}
@@ -0,0 +1,191 @@
+use crate::documents::DocumentRef;
+use crate::errors::Result;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// The newtype struct name that the [`super::Serializer`] recognises to write a `geoPointValue`
+pub(crate) const GEO_POINT_TOKEN: &str = "$firestore_db_and_auth::GeoPoint";
+/// The newtype struct name that the [`super::Serializer`] recognises to write a `referenceValue`
+pub(crate) const REFERENCE_TOKEN: &str = "$firestore_db_and_auth::DocumentReference";
+
+/// Binary data that is stored as Firestore `bytesValue`.
+///
+/// A plain `Vec<u8>` is stored as an array of integers.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for Bytes {
+    fn from(v: Vec<u8>) -> Self {
+        Bytes(v)
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(v: Bytes) -> Self {
+        v.0
+    }
+}
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Bytes, E> {
+        Ok(Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<Bytes, E> {
+        Ok(Bytes(v))
+    }
+
+    // Formats without a bytes type, like json, write a sequence of integers
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Bytes, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Bytes(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// A geographical location that is stored as Firestore `geoPointValue`.
+///
+/// With other serde formats, for example json, this is a map with "latitude" and "longitude".
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct GeoPoint {
+    /// The latitude in degrees, in the range [-90.0, +90.0]
+    pub latitude: f64,
+    /// The longitude in degrees, in the range [-180.0, +180.0]
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// Creates a geo point from latitude and longitude in degrees
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        GeoPoint { latitude, longitude }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct LatLng {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl Serialize for GeoPoint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let lat_lng = LatLng {
+            latitude: self.latitude,
+            longitude: self.longitude,
+        };
+        serializer.serialize_newtype_struct(GEO_POINT_TOKEN, &lat_lng)
+    }
+}
+
+struct GeoPointVisitor;
+
+impl<'de> Visitor<'de> for GeoPointVisitor {
+    type Value = GeoPoint;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a geo point")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<GeoPoint, D::Error> {
+        let lat_lng = LatLng::deserialize(deserializer)?;
+        Ok(GeoPoint::new(lat_lng.latitude, lat_lng.longitude))
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoPoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(GEO_POINT_TOKEN, GeoPointVisitor)
+    }
+}
+
+/// A reference to another document that is stored as Firestore `referenceValue`.
+///
+/// Contains the absolute document name like
+/// "projects/{PROJECT_ID}/databases/(default)/documents/my_collection/document_id".
+///
+/// Example:
+/// ```
+/// use firestore_db_and_auth::documents::{DocumentRef, DEFAULT_DATABASE_ID};
+/// use firestore_db_and_auth::values::DocumentReference;
+///
+/// let alice = DocumentRef::new("users/alice")?;
+/// let reference = DocumentReference::new(alice.name_in_database("my-project", DEFAULT_DATABASE_ID));
+/// assert_eq!(reference.document_ref()?, alice);
+/// # Ok::<(), firestore_db_and_auth::errors::FirebaseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DocumentReference(pub String);
+
+impl DocumentReference {
+    /// Creates a reference from an absolute document name
+    pub fn new(name: impl Into<String>) -> Self {
+        DocumentReference(name.into())
+    }
+
+    /// The absolute document name
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// The relative document path, that can be used with the functions of [`crate::documents`].
+    /// Returns [`crate::errors::FirebaseError::InvalidPath`] if the name is not a document name.
+    pub fn document_ref(&self) -> Result<DocumentRef> {
+        DocumentRef::from_name(&self.0)
+    }
+}
+
+impl Serialize for DocumentReference {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(REFERENCE_TOKEN, &self.0)
+    }
+}
+
+struct DocumentReferenceVisitor;
+
+impl<'de> Visitor<'de> for DocumentReferenceVisitor {
+    type Value = DocumentReference;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a document reference")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<DocumentReference, E> {
+        Ok(DocumentReference(v.to_owned()))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<DocumentReference, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DocumentReference {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(REFERENCE_TOKEN, DocumentReferenceVisitor)
+    }
+}